@@ -0,0 +1,49 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// With the `embed-inputs` feature, generate a table of every puzzle input
+/// in `input/` for `input::embedded` to include in the binary. Without it the
+/// table is empty.
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_dir = Path::new(&manifest_dir).join("input");
+    println!("cargo:rerun-if-changed={}", input_dir.display());
+
+    let mut inputs = Vec::new();
+    if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_some() {
+        if let Ok(entries) = fs::read_dir(&input_dir) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if entry.path().is_file() && is_input_name(&name) {
+                    println!("cargo:rerun-if-changed={}", entry.path().display());
+                    inputs.push((name, entry.path()));
+                }
+            }
+        }
+    }
+    inputs.sort();
+
+    let table = inputs.iter()
+        .map(|(name, path)| format!("    ({:?}, include_str!({:?})),\n", name, path.display().to_string()))
+        .collect::<String>();
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("embedded_inputs.rs");
+    fs::write(out, format!("&[\n{}]\n", table)).unwrap();
+}
+
+/// Inputs are named `dayN` or `dayN_partP`, as `input::resolve` looks for
+fn is_input_name(name: &str) -> bool {
+    let rest = match name.strip_prefix("day") {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    let (day, part) = match rest.split_once("_part") {
+        Some((day, part)) => (day, Some(part)),
+        None => (rest, None),
+    };
+
+    let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    is_number(day) && part.is_none_or(is_number)
+}
@@ -0,0 +1,55 @@
+/* Generated by `aoc21 header` - do not edit by hand */
+#ifndef AOC21_H
+#define AOC21_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define AOC21_PLUGIN_ABI_VERSION 1
+
+/* ok */
+#define AOC21_STATUS_OK 0
+/* no such part */
+#define AOC21_STATUS_NO_SUCH_PART 1
+/* invalid input */
+#define AOC21_STATUS_INVALID_INPUT 2
+/* output buffer too small */
+#define AOC21_STATUS_BUFFER_TOO_SMALL 3
+/* no such day */
+#define AOC21_STATUS_NO_SUCH_DAY 4
+/* null pointer */
+#define AOC21_STATUS_NULL_POINTER 5
+/* challenge panicked */
+#define AOC21_STATUS_PANIC 6
+
+/*
+ * Solve one part of a built-in challenge. On entry `out_len` holds the
+ * capacity of `out_buf`; on success it holds the length of the answer, which
+ * is not nul-terminated. Returns one of the AOC21_STATUS_* codes, with
+ * AOC21_STATUS_INVALID_INPUT for input the challenge can't make sense of
+ * and AOC21_STATUS_PANIC if it fails for any other reason.
+ */
+int32_t aoc21_solve(uint8_t day, uint8_t part,
+                    const uint8_t *input, size_t input_len,
+                    uint8_t *out_buf, size_t *out_len);
+
+/* Plugin entry point solving one part of a challenge, as for aoc21_solve */
+typedef int32_t (*aoc21_solve_fn)(uint8_t part,
+                                  const uint8_t *input, size_t input_len,
+                                  uint8_t *out_buf, size_t *out_len);
+
+/* One challenge implementation exported by a plugin */
+struct aoc21_challenge {
+    uint8_t day;
+    const char *name;
+    aoc21_solve_fn solve;
+};
+
+/*
+ * Plugins export these two functions: the ABI version they were built
+ * against, and a table of the challenges they implement.
+ */
+uint32_t aoc21_plugin_abi_version(void);
+const struct aoc21_challenge *aoc21_plugin_challenges(size_t *count);
+
+#endif /* AOC21_H */
@@ -0,0 +1,80 @@
+use std::os::raw::c_char;
+
+/// Version of the C-compatible plugin ABI. Plugins report the version they
+/// were built against and are refused if it doesn't match exactly.
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Symbol returning the plugin's ABI version as a `u32`
+pub const VERSION_SYMBOL: &[u8] = b"aoc21_plugin_abi_version\0";
+
+/// Symbol returning the plugin's challenge table
+pub const CHALLENGES_SYMBOL: &[u8] = b"aoc21_plugin_challenges\0";
+
+/// The answer was written to the output buffer
+pub const STATUS_OK: i32 = 0;
+
+/// The challenge has no such part
+pub const STATUS_NO_SUCH_PART: i32 = 1;
+
+/// The challenge could not make sense of its input
+pub const STATUS_INVALID_INPUT: i32 = 2;
+
+/// The output buffer is too small; the required length has been written to
+/// the length pointer
+pub const STATUS_BUFFER_TOO_SMALL: i32 = 3;
+
+/// No challenge is registered for the requested day
+pub const STATUS_NO_SUCH_DAY: i32 = 4;
+
+/// A required pointer argument was null
+pub const STATUS_NULL_POINTER: i32 = 5;
+
+/// The challenge panicked while solving; the panic was caught before it
+/// could cross the boundary
+pub const STATUS_PANIC: i32 = 6;
+
+/// Describe a status code returned across the ABI
+pub fn describe_status(status: i32) -> &'static str {
+    match status {
+        STATUS_OK => "ok",
+        STATUS_NO_SUCH_PART => "no such part",
+        STATUS_INVALID_INPUT => "invalid input",
+        STATUS_BUFFER_TOO_SMALL => "output buffer too small",
+        STATUS_NO_SUCH_DAY => "no such day",
+        STATUS_NULL_POINTER => "null pointer",
+        STATUS_PANIC => "challenge panicked",
+        _ => "unknown error",
+    }
+}
+
+/// Solve one part of a challenge. The input is `input_len` bytes of UTF-8
+/// with lines separated by `\n`. On entry `out_len` holds the capacity of
+/// `out_buf`; on success it holds the length of the UTF-8 answer written
+/// there.
+pub type SolveFn = unsafe extern "C" fn(
+    part: u8,
+    input: *const u8,
+    input_len: usize,
+    out_buf: *mut u8,
+    out_len: *mut usize,
+) -> i32;
+
+/// Returns the plugin's ABI version
+pub type VersionFn = unsafe extern "C" fn() -> u32;
+
+/// Returns a pointer to the plugin's challenge table and writes its length
+/// to `count`. The table must live as long as the library stays loaded.
+pub type ChallengesFn = unsafe extern "C" fn(count: *mut usize) -> *const ChallengeDescriptor;
+
+/// One challenge implementation exported by a plugin
+#[repr(C)]
+pub struct ChallengeDescriptor {
+    /// Day of the challenge
+    pub day: u8,
+
+    /// Nul-terminated name of the implementation, used to select it with
+    /// `--impl`
+    pub name: *const c_char,
+
+    pub solve: SolveFn,
+}
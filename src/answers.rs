@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::client::Verdict;
+
+/// File in the cache directory the store is kept in
+pub const ANSWERS_FILE: &str = "answers.json";
+
+#[derive(Debug)]
+pub enum StoreError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, String),
+}
+
+impl Display for StoreError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Io(path, err) =>
+                write!(f, "cannot access answers {}: {}", path.display(), err),
+            StoreError::Parse(path, reason) =>
+                write!(f, "cannot parse answers {}: {}", path.display(), reason),
+        }
+    }
+}
+
+/// Everything we've learned by submitting answers for one part of a day
+#[derive(Debug, Default, PartialEq)]
+pub struct Entry {
+    pub correct: Option<String>,
+
+    /// Answers the site rejected, with its verdict on each
+    pub wrong: Vec<(String, Verdict)>,
+}
+
+/// What the store already says about an answer, making a submission
+/// unnecessary
+#[derive(Debug, PartialEq)]
+pub enum Known {
+    Correct,
+
+    /// The part is solved, with a different answer
+    Incorrect(String),
+
+    /// The same answer was rejected before
+    Rejected(Verdict),
+
+    /// A guess on the same side of this one was too high or too low
+    OutOfBounds(Verdict, String),
+}
+
+impl Display for Known {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Known::Correct => write!(f, "already submitted: correct"),
+            Known::Incorrect(correct) => write!(f, "already solved: the answer is {}", correct),
+            Known::Rejected(verdict) => write!(f, "already submitted: {}", verdict),
+            Known::OutOfBounds(verdict, guess) => write!(f, "{}: {} was already {}", verdict, guess, verdict),
+        }
+    }
+}
+
+/// Submitted answers and their outcomes, kept as JSON keyed by
+/// `year/day/part`
+pub struct AnswerStore {
+    path: PathBuf,
+    entries: BTreeMap<(u16, u8, u8), Entry>,
+}
+
+impl AnswerStore {
+    /// Load the store from the cache directory, starting a new one if it
+    /// doesn't exist yet
+    pub fn load(cache_dir: &Path) -> Result<Self, StoreError> {
+        let path = cache_dir.join(ANSWERS_FILE);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound =>
+                return Ok(AnswerStore { path, entries: BTreeMap::new() }),
+            Err(err) => return Err(StoreError::Io(path, err)),
+        };
+
+        let entries = AnswerStore::parse(&text)
+            .map_err(|reason| StoreError::Parse(path.clone(), reason))?;
+        Ok(AnswerStore { path, entries })
+    }
+
+    fn parse(text: &str) -> Result<BTreeMap<(u16, u8, u8), Entry>, String> {
+        let value = serde_json::from_str::<Value>(text)
+            .map_err(|err| err.to_string())?;
+        let object = value.as_object()
+            .ok_or("expected an object")?;
+
+        object.iter()
+            .map(|(key, entry)| {
+                let invalid = || format!("invalid key: {}", key);
+                let parsed = match key.split('/').collect::<Vec<_>>()[..] {
+                    [year, day, part] => (year.parse::<u16>(), day.parse::<u8>(), part.parse::<u8>()),
+                    _ => return Err(invalid()),
+                };
+                let key = match parsed {
+                    (Ok(year), Ok(day @ 1..=25), Ok(part @ 1..=2)) => (year, day, part),
+                    _ => return Err(invalid()),
+                };
+
+                let correct = entry["correct"].as_str().map(String::from);
+                let wrong = entry["wrong"].as_array()
+                    .map(|wrong| wrong.iter()
+                        .map(|guess| {
+                            let answer = guess["answer"].as_str();
+                            let verdict = match guess["verdict"].as_str() {
+                                Some("too high") => Some(Verdict::TooHigh),
+                                Some("too low") => Some(Verdict::TooLow),
+                                Some("wrong") => Some(Verdict::Wrong),
+                                _ => None,
+                            };
+                            answer.zip(verdict)
+                                .map(|(answer, verdict)| (answer.to_string(), verdict))
+                                .ok_or_else(|| format!("invalid guess: {}", guess))
+                        })
+                        .collect::<Result<Vec<_>, _>>())
+                    .transpose()?
+                    .unwrap_or_default();
+
+                Ok((key, Entry { correct, wrong }))
+            })
+            .collect()
+    }
+
+    pub fn save(&self) -> Result<(), StoreError> {
+        let object = self.entries.iter()
+            .map(|((year, day, part), entry)| {
+                let wrong = entry.wrong.iter()
+                    .map(|(answer, verdict)| json!({ "answer": answer, "verdict": verdict.to_string() }))
+                    .collect::<Vec<_>>();
+                (format!("{}/{}/{}", year, day, part), json!({ "correct": entry.correct, "wrong": wrong }))
+            })
+            .collect::<serde_json::Map<_, _>>();
+
+        let text = serde_json::to_string_pretty(&Value::Object(object)).unwrap();
+        self.path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&self.path, text + "\n"))
+            .map_err(|err| StoreError::Io(self.path.clone(), err))
+    }
+
+    pub fn get(&self, year: u16, day: u8, part: u8) -> Option<&Entry> {
+        self.entries.get(&(year, day, part))
+    }
+
+    /// Find out whether submitting an answer could tell us anything new
+    pub fn check(&self, year: u16, day: u8, part: u8, answer: &str) -> Option<Known> {
+        let entry = self.get(year, day, part)?;
+
+        if let Some(correct) = &entry.correct {
+            return Some(if correct == answer {
+                Known::Correct
+            } else {
+                Known::Incorrect(correct.clone())
+            });
+        }
+
+        if let Some((_, verdict)) = entry.wrong.iter().find(|(guess, _)| guess == answer) {
+            return Some(Known::Rejected(verdict.clone()));
+        }
+
+        // A number past a guess that was already too high or too low is
+        // wrong the same way
+        let number = answer.parse::<i128>().ok()?;
+        entry.wrong.iter()
+            .find(|(guess, verdict)| match (guess.parse::<i128>(), verdict) {
+                (Ok(guess), Verdict::TooHigh) => number >= guess,
+                (Ok(guess), Verdict::TooLow) => number <= guess,
+                _ => false,
+            })
+            .map(|(guess, verdict)| Known::OutOfBounds(verdict.clone(), guess.clone()))
+    }
+
+    /// Remember what the site said about an answer. Only verdicts on the
+    /// answer itself are kept.
+    pub fn record(&mut self, year: u16, day: u8, part: u8, answer: &str, verdict: &Verdict) {
+        let entry = self.entries.entry((year, day, part)).or_default();
+        match verdict {
+            Verdict::Correct => entry.correct = Some(answer.to_string()),
+            verdict if verdict.is_wrong() => entry.wrong.push((answer.to_string(), verdict.clone())),
+            _ => {},
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let mut store = AnswerStore { path: PathBuf::from(ANSWERS_FILE), entries: BTreeMap::new() };
+        store.record(2021, 1, 1, "1200", &Verdict::TooHigh);
+        store.record(2021, 1, 1, "900", &Verdict::TooLow);
+        store.record(2021, 1, 1, "abc", &Verdict::Wrong);
+        store.record(2021, 1, 1, "1000", &Verdict::RateLimited(None));
+
+        assert_eq!(store.check(2021, 1, 1, "abc"), Some(Known::Rejected(Verdict::Wrong)));
+        assert_eq!(store.check(2021, 1, 1, "1300"), Some(Known::OutOfBounds(Verdict::TooHigh, String::from("1200"))));
+        assert_eq!(store.check(2021, 1, 1, "900"), Some(Known::Rejected(Verdict::TooLow)));
+        assert_eq!(store.check(2021, 1, 1, "850"), Some(Known::OutOfBounds(Verdict::TooLow, String::from("900"))));
+        assert_eq!(store.check(2021, 1, 1, "1000"), None);
+        assert_eq!(store.check(2021, 1, 2, "1000"), None);
+
+        store.record(2021, 1, 1, "1000", &Verdict::Correct);
+        assert_eq!(store.check(2021, 1, 1, "1000"), Some(Known::Correct));
+        assert_eq!(store.check(2021, 1, 1, "1001"), Some(Known::Incorrect(String::from("1000"))));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = std::env::temp_dir().join(format!("aoc21-answers-{}", std::process::id()));
+        let mut store = AnswerStore::load(&dir).unwrap();
+        store.record(2021, 6, 2, "26984457539", &Verdict::Correct);
+        store.record(2021, 6, 1, "10", &Verdict::TooLow);
+        store.save().unwrap();
+
+        let loaded = AnswerStore::load(&dir).unwrap();
+        assert_eq!(loaded.entries, store.entries);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_key() {
+        let entry = r#"{ "correct": "1" }"#;
+        assert!(AnswerStore::parse(&format!(r#"{{ "2021/6/2": {} }}"#, entry)).is_ok());
+        for key in ["2021/262/1", "2021/6/3", "2021/0/1", "2021/6", "2021/6/1/1", "70000/6/1"] {
+            assert_eq!(AnswerStore::parse(&format!(r#"{{ "{}": {} }}"#, key, entry)).err(),
+                Some(format!("invalid key: {}", key)));
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Panic payload used to unwind out of a challenge that has been cancelled
+pub struct Cancelled;
+
+/// Shared flag that long-running challenges check so they can be stopped
+/// from another thread. Clones share the same flag.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Unwind out of the current challenge if it has been cancelled. This
+    /// uses `resume_unwind` rather than `panic!` so that no panic message is
+    /// printed for what is an expected way to stop.
+    pub fn check(&self) {
+        if self.is_cancelled() {
+            panic::resume_unwind(Box::new(Cancelled));
+        }
+    }
+}
@@ -1,88 +1,677 @@
-use itertools::Itertools;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fmt::{self, Display, Formatter, Write};
 
-use crate::challenges::Challenge;
+use crate::challenges::{self, Challenge};
+use crate::counter::Counter;
 
-pub struct Day1 {}
+/// The last `size` depth measurements of the sonar sweep, kept in a ring
+/// buffer so a sweep of any length needs only `size` slots
+struct Window {
+    depths: Vec<i64>,
+    size: usize,
+    next: usize,
+    sum: i64,
+}
 
-impl Day1 {
-    pub fn new() -> Day1 {
-        Day1 {}
+impl Window {
+    fn new(size: usize) -> Self {
+        Window {
+            depths: Vec::with_capacity(size),
+            size,
+            next: 0,
+            sum: 0,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.depths.len() == self.size
+    }
+
+    /// Add a measurement to the window, returning the one that drops out of
+    /// it once the window is full
+    fn push(&mut self, depth: i64) -> Option<i64> {
+        self.sum += depth;
+        if !self.is_full() {
+            self.depths.push(depth);
+            return None;
+        }
+
+        let oldest = std::mem::replace(&mut self.depths[self.next], depth);
+        self.next = (self.next + 1) % self.size;
+        self.sum -= oldest;
+        Some(oldest)
     }
 }
 
-impl Challenge for Day1 {
-    fn part_1(&self, input: Vec<String>) -> String {
-        input.into_iter()
+/// The sum of one full window of measurements, and how it compares to the
+/// window before it
+#[derive(Debug, PartialEq)]
+struct Step {
+    sum: i64,
 
-            // Each line of the input is an integer
-            .map(|line| line.parse::<i64>().unwrap())
+    /// Missing for the first window, which has nothing to compare with
+    change: Option<Ordering>,
+}
 
-            // Iterate over every pair of sequential numbers
-            .tuple_windows()
+/// Sliding window sums over a stream of measurements.
+///
+/// Neighbouring windows share every measurement but the first of one and
+/// the last of the other, so instead of summing them to compare, each new
+/// measurement `a[i+size]` is compared with the `a[i]` leaving the window.
+struct Sweep<I> {
+    depths: I,
+    window: Window,
+}
 
-            // Count every time an entry is larger than the previous
-            .fold(0, |acc, n| match n {
-                (x, y) if y > x => acc + 1,
-                _ => acc,
-            })
-            .to_string()
+impl<I: Iterator<Item = i64>> Sweep<I> {
+    fn new<D: IntoIterator<IntoIter = I>>(depths: D, size: usize) -> Self {
+        Sweep {
+            depths: depths.into_iter(),
+            window: Window::new(size),
+        }
     }
+}
 
-    fn part_2(&self, input: Vec<String>) -> String {
-        input.into_iter()
+impl<I: Iterator<Item = i64>> Iterator for Sweep<I> {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let depth = self.depths.next()?;
+            let oldest = self.window.push(depth);
+
+            if self.window.is_full() {
+                return Some(Step {
+                    sum: self.window.sum,
+                    change: oldest.map(|oldest| depth.cmp(&oldest)),
+                });
+            }
+        }
+    }
+}
+
+/// Count how often the sum of `size` consecutive measurements is larger than
+/// the sum of the previous `size`
+fn count_increases<I: IntoIterator<Item = i64>>(depths: I, size: usize) -> usize {
+    Sweep::new(depths, size)
+        .filter(|step| step.change == Some(Ordering::Greater))
+        .count()
+}
+
+/// Label every window sum with how it changed, as the puzzle does, then
+/// total up each kind of change. Single measurements are listed as they
+/// are; larger windows are lettered like spreadsheet columns.
+fn explain<I: IntoIterator<Item = i64>>(depths: I, size: usize) -> String {
+    let mut text = String::new();
+    let mut changes = Counter::new();
+
+    for (i, step) in Sweep::new(depths, size).enumerate() {
+        if size > 1 {
+            write!(text, "{}: ", window_label(i)).unwrap();
+        }
+
+        let annotation = match step.change {
+            None if size == 1 => "N/A - no previous measurement",
+            None => "N/A - no previous sum",
+            Some(change) => describe(change),
+        };
+        writeln!(text, "{} ({})", step.sum, annotation).unwrap();
+
+        changes.extend(step.change);
+    }
+
+    writeln!(text).unwrap();
+    for change in [Ordering::Greater, Ordering::Less, Ordering::Equal] {
+        writeln!(text, "{}: {}", describe(change), changes.get(&change)).unwrap();
+    }
+
+    text
+}
+
+fn describe(change: Ordering) -> &'static str {
+    match change {
+        Ordering::Greater => "increased",
+        Ordering::Less => "decreased",
+        Ordering::Equal => "no change",
+    }
+}
+
+/// Letter the windows `A` to `Z`, then `AA`, `AB` and so on
+fn window_label(index: usize) -> String {
+    let mut label = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        n -= 1;
+        label.push((b'A' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+
+    label.iter().rev().collect()
+}
+
+/// Largest size of the depth chart, in characters. Longer sweeps are
+/// averaged down to fit.
+const CHART_WIDTH: usize = 60;
+const CHART_HEIGHT: usize = 12;
+
+/// Consecutive measurements, by index, with the first and last included
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Run {
+    start: usize,
+    end: usize,
+}
+
+impl Run {
+    fn len(&self) -> usize {
+        self.end - self.start + 1
+    }
+}
+
+/// Statistics over the depths of a whole sweep, for getting a feel for the
+/// sonar data
+struct Profile {
+    depths: Vec<i64>,
+
+    /// The line in the input of each depth
+    lines: Vec<usize>,
+}
+
+impl Profile {
+    fn new<I: IntoIterator<Item = (usize, i64)>>(readings: I) -> Self {
+        let (lines, depths) = readings.into_iter().unzip();
+        Profile { depths, lines }
+    }
 
-            // Parse every entry into an integer
-            .map(|line| line.parse::<i64>().unwrap())
+    /// The longest run of measurements each comparing to the one before in
+    /// the same way, if any pair does. The earliest wins a tie.
+    fn longest_run(&self, change: Ordering) -> Option<Run> {
+        let mut best: Option<Run> = None;
+        let mut start = 0;
 
-            // Iterate over every group of 3 sequential numbers to create a
-            // three measurement sliding window.
-            .tuple_windows::<(_, _, _)>()
+        for end in 1..self.depths.len() {
+            if self.depths[end].cmp(&self.depths[end - 1]) != change {
+                start = end;
+                continue;
+            }
 
-            // Collapse each window into its sum
-            .map(|(a, b, c)| a + b + c)
+            let run = Run { start, end };
+            if best.is_none_or(|best| run.len() > best.len()) {
+                best = Some(run);
+            }
+        }
 
-            // Iterate over every pair of sums from the previous steps
-            .tuple_windows()
+        best
+    }
+
+    /// The pair of neighbouring measurements with the biggest decrease
+    /// between them, if the depth ever decreases
+    fn largest_drop(&self) -> Option<Run> {
+        (1..self.depths.len())
+            .map(|end| Run { start: end - 1, end })
+            .filter(|run| self.drop(run) > 0)
+            .rev()
+            .max_by_key(|run| self.drop(run))
+    }
+
+    fn drop(&self, run: &Run) -> i64 {
+        self.depths[run.start] - self.depths[run.end]
+    }
 
-            // Count every time a sum has increased
-            .fold(0, |acc, n| match n {
-                (x, y) if y > x => acc + 1,
-                _ => acc,
+    /// Describe a run by its lines in the input, and its depths at either end
+    fn describe_run(&self, run: Option<Run>) -> String {
+        match run {
+            Some(run) => format!("{} measurements, lines {}-{} ({} -> {})",
+                run.len(), self.lines[run.start], self.lines[run.end], self.depths[run.start], self.depths[run.end]),
+            None => String::from("none"),
+        }
+    }
+
+    /// Plot depth against measurement, with the surface at the top. Each
+    /// column averages an equal share of the measurements and is filled in
+    /// from its depth down, so the chart reads as the shape of the sea floor.
+    fn chart(&self) -> String {
+        let count = self.depths.len();
+        let width = count.min(CHART_WIDTH);
+        let columns = (0..width)
+            .map(|column| {
+                let bucket = &self.depths[column * count / width..(column + 1) * count / width];
+                bucket.iter().sum::<i64>() as f64 / bucket.len() as f64
             })
-            .to_string()
+            .collect::<Vec<_>>();
+
+        let min = columns.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = columns.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let row_of = |depth: f64| match max - min {
+            range if range > 0.0 => ((depth - min) / range * (CHART_HEIGHT - 1) as f64).round() as usize,
+            _ => 0,
+        };
+
+        let labels = [format!("{:.0}", min), format!("{:.0}", max)];
+        let margin = labels.iter().map(|label| label.len()).max().unwrap();
+
+        let mut text = String::new();
+        for row in 0..CHART_HEIGHT {
+            let label = match row {
+                0 => &labels[0],
+                _ if row == CHART_HEIGHT - 1 => &labels[1],
+                _ => "",
+            };
+
+            let line = columns.iter()
+                .map(|&depth| if row_of(depth) <= row { '#' } else { ' ' })
+                .collect::<String>();
+            writeln!(text, "{:>margin$} |{}", label, line.trim_end(), margin = margin).unwrap();
+        }
+
+        writeln!(text, "{:>margin$} +{}", "", "-".repeat(width), margin = margin).unwrap();
+        let (first, last) = (self.lines[0].to_string(), self.lines[count - 1].to_string());
+        writeln!(text, "{:>margin$}  {}{:>pad$}", "", first, last, margin = margin,
+            pad = width.saturating_sub(first.len()).max(last.len() + 1)).unwrap();
+
+        text
+    }
+}
+
+impl Display for Profile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.depths.is_empty() {
+            return writeln!(f, "no measurements");
+        }
+
+        let readings = || self.lines.iter().zip(&self.depths);
+        let (min_line, min) = readings().min_by_key(|(_, depth)| **depth).unwrap();
+        let (max_line, max) = readings().rev().max_by_key(|(_, depth)| **depth).unwrap();
+        let mean = self.depths.iter().sum::<i64>() as f64 / self.depths.len() as f64;
+
+        writeln!(f, "measurements: {}", self.depths.len())?;
+        writeln!(f, "min depth: {} (line {})", min, min_line)?;
+        writeln!(f, "max depth: {} (line {})", max, max_line)?;
+        writeln!(f, "mean depth: {:.1}", mean)?;
+        writeln!(f, "longest increase: {}", self.describe_run(self.longest_run(Ordering::Greater)))?;
+        writeln!(f, "longest decrease: {}", self.describe_run(self.longest_run(Ordering::Less)))?;
+
+        match self.largest_drop() {
+            Some(run) => writeln!(f, "largest drop: {}, lines {}-{} ({} -> {})", self.drop(&run),
+                self.lines[run.start], self.lines[run.end], self.depths[run.start], self.depths[run.end])?,
+            None => writeln!(f, "largest drop: none")?,
+        }
+
+        write!(f, "\n{}", self.chart())
+    }
+}
+
+/// What to do with a line of the input that isn't a depth
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Policy {
+    /// Stop at the first bad line
+    Strict,
+
+    /// Leave bad lines out of the sweep
+    Skip,
+
+    /// Fill in bad lines along a straight line between the good depths
+    /// either side of them
+    Interpolate,
+}
+
+impl Policy {
+    fn parse(value: &str) -> Result<Policy, String> {
+        match value {
+            "strict" => Ok(Policy::Strict),
+            "skip" => Ok(Policy::Skip),
+            "interpolate" => Ok(Policy::Interpolate),
+            _ => Err(format!("invalid parse policy: {}", value)),
+        }
+    }
+}
+
+/// A line of the input that isn't a depth
+#[derive(Debug, PartialEq)]
+struct Anomaly {
+    line: usize,
+    text: String,
+
+    /// The depth interpolated in its place
+    filled: Option<i64>,
+}
+
+impl Display for Anomaly {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.text.trim() {
+            "" => write!(f, "line {}: blank", self.line)?,
+            text => write!(f, "line {}: invalid depth `{}`", self.line, text)?,
+        }
+
+        match self.filled {
+            Some(depth) => write!(f, " (filled in as {})", depth),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Depths parsed from the input one line at a time, along with the line
+/// each came from. Each line of the input should be an integer; the policy
+/// decides what happens to those that aren't, and they're kept as
+/// anomalies to report afterwards.
+struct Readings<I> {
+    lines: std::iter::Enumerate<I>,
+    policy: Policy,
+    anomalies: Vec<Anomaly>,
+
+    /// The last good depth, to interpolate from
+    last: Option<i64>,
+
+    /// Number of bad lines since the last good depth, which are the last
+    /// anomalies, waiting for the next good depth to interpolate to
+    gap: usize,
+
+    /// Readings ready to hand out once a gap has been filled in
+    pending: VecDeque<(usize, i64)>,
+}
+
+impl<I: Iterator<Item = String>> Readings<I> {
+    fn new<L: IntoIterator<IntoIter = I>>(lines: L, policy: Policy) -> Self {
+        Readings {
+            lines: lines.into_iter().enumerate(),
+            policy,
+            anomalies: Vec::new(),
+            last: None,
+            gap: 0,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Fill in the current gap with depths stepping evenly from the last
+    /// good depth to `next`. A gap at the start or end of the input only
+    /// has one neighbour, so it takes that depth throughout.
+    fn fill_gap(&mut self, next: Option<i64>) {
+        let (from, to) = match (self.last, next) {
+            (Some(from), Some(to)) => (from, to),
+            (Some(depth), None) | (None, Some(depth)) => (depth, depth),
+            (None, None) => return,
+        };
+
+        let steps = (self.gap + 1) as f64;
+        let start = self.anomalies.len() - self.gap;
+        for (i, anomaly) in self.anomalies[start..].iter_mut().enumerate() {
+            let depth = from + ((to - from) as f64 * (i + 1) as f64 / steps).round() as i64;
+            anomaly.filled = Some(depth);
+            self.pending.push_back((anomaly.line, depth));
+        }
+
+        self.gap = 0;
+    }
+}
+
+impl<I: Iterator<Item = String>> Iterator for Readings<I> {
+    type Item = (usize, i64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(reading) = self.pending.pop_front() {
+                return Some(reading);
+            }
+
+            let (index, text) = match self.lines.next() {
+                Some(line) => line,
+                None => {
+                    self.fill_gap(None);
+                    return self.pending.pop_front();
+                },
+            };
+
+            let line = index + 1;
+            let depth = match text.trim().parse::<i64>() {
+                Ok(depth) => depth,
+                Err(_) => {
+                    let anomaly = Anomaly { line, text, filled: None };
+                    match self.policy {
+                        Policy::Strict => challenges::invalid_input(anomaly),
+                        Policy::Skip => {},
+                        Policy::Interpolate => self.gap += 1,
+                    }
+
+                    self.anomalies.push(anomaly);
+                    continue;
+                },
+            };
+
+            if self.gap > 0 {
+                self.fill_gap(Some(depth));
+            }
+
+            self.last = Some(depth);
+            self.pending.push_back((line, depth));
+        }
+    }
+}
+
+fn parse_window(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(size) if size > 0 => Ok(size),
+        _ => Err(format!("invalid window size: {}", value)),
+    }
+}
+
+/// What a part gives as its answer
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Mode {
+    /// Just the number of increases, as the puzzle asks for
+    Count,
+
+    /// Every window sum labelled with how it changed, and totals of each
+    /// change
+    Explain,
+
+    /// Statistics and a chart of the raw measurements, the same for either
+    /// part
+    Analyze,
+}
+
+impl Mode {
+    fn parse(value: &str) -> Result<Mode, String> {
+        match value {
+            "count" => Ok(Mode::Count),
+            "explain" => Ok(Mode::Explain),
+            "analyze" => Ok(Mode::Analyze),
+            _ => Err(format!("invalid mode: {}", value)),
+        }
+    }
+}
+
+pub struct Day1 {
+    /// Number of measurements in each sliding window, overriding the
+    /// puzzle's 1 and 3
+    window: Option<usize>,
+    mode: Mode,
+    policy: Policy,
+}
+
+impl Day1 {
+    pub fn new() -> Day1 {
+        Day1 {
+            window: None,
+            mode: Mode::Count,
+            policy: Policy::Strict,
+        }
+    }
+
+    fn sweep(&self, input: Vec<String>, size: usize) -> String {
+        let mut readings = Readings::new(input, self.policy);
+        let mut text = match self.mode {
+            Mode::Count => count_increases(readings.by_ref().map(|(_, depth)| depth), size).to_string(),
+            Mode::Explain => explain(readings.by_ref().map(|(_, depth)| depth), size),
+            Mode::Analyze => Profile::new(readings.by_ref()).to_string(),
+        };
+
+        // List whatever the policy let through after the answer
+        let anomalies = &readings.anomalies;
+        if !anomalies.is_empty() {
+            if !text.ends_with('\n') {
+                text.push('\n');
+            }
+
+            let action = match self.policy {
+                Policy::Interpolate => "interpolated",
+                _ => "skipped",
+            };
+            let noun = if anomalies.len() == 1 { "line" } else { "lines" };
+            writeln!(text, "\n{} {} {}:", action, anomalies.len(), noun).unwrap();
+            for anomaly in anomalies {
+                writeln!(text, "{}", anomaly).unwrap();
+            }
+        }
+
+        text
+    }
+}
+
+impl Challenge for Day1 {
+    fn part_1(&self, input: Vec<String>) -> String {
+        // Comparing each measurement with the previous one is a sliding
+        // window of one
+        self.sweep(input, self.window.unwrap_or(1))
+    }
+
+    fn part_2(&self, input: Vec<String>) -> String {
+        self.sweep(input, self.window.unwrap_or(3))
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name {
+            "window" => self.window = Some(parse_window(value)?),
+            "mode" => self.mode = Mode::parse(value)?,
+            "parse" => self.policy = Policy::parse(value)?,
+            _ => return Err(format!("unknown parameter `{}`", name)),
+        }
+
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::challenges::input_from_str;
+    use crate::challenges::{self, input_from_str};
 
     // Example data from the challenge description
-    const DATA: &'static str = r"
-        199
-        200
-        208
-        210
-        200
-        207
-        240
-        269
-        260
-        263
-    ";
+    fn data() -> Vec<String> {
+        input_from_str(&challenges::example(1).input)
+    }
 
     #[test]
     fn test_part_1() {
-        let input = input_from_str(DATA);
         let challenge = Day1::new();
-        assert_eq!(challenge.part_1(input), "7");
+        assert_eq!(Some(challenge.part_1(data())), challenges::example(1).answers[0]);
     }
 
     #[test]
     fn test_part_2() {
-        let input = input_from_str(DATA);
         let challenge = Day1::new();
-        assert_eq!(challenge.part_2(input), "5");
+        assert_eq!(Some(challenge.part_2(data())), challenges::example(1).answers[1]);
+    }
+
+    #[test]
+    fn test_window_sizes() {
+        let depths = || Readings::new(data(), Policy::Strict).map(|(_, depth)| depth);
+
+        // Window sums for a size of 4 are 817, 818, 825, 857, 916, 976, 1032
+        assert_eq!(count_increases(depths(), 4), 6);
+        assert_eq!(count_increases(depths(), 10), 0);
+        assert_eq!(count_increases(depths(), 20), 0);
+
+        let mut challenge = Day1::new();
+        assert!(challenge.set_param("window", "0").is_err());
+        challenge.set_param("window", "2").unwrap();
+        assert_eq!(challenge.part_1(data()), "5");
+    }
+
+    #[test]
+    fn test_explain() {
+        let mut challenge = Day1::new();
+        challenge.set_param("mode", "explain").unwrap();
+
+        let part_1 = challenge.part_1(data());
+        assert!(part_1.starts_with("199 (N/A - no previous measurement)\n200 (increased)\n"));
+        assert!(part_1.contains("210 (increased)\n200 (decreased)\n"));
+        assert!(part_1.ends_with("\nincreased: 7\ndecreased: 2\nno change: 0\n"));
+
+        let part_2 = challenge.part_2(data());
+        assert_eq!(part_2, "\
+            A: 607 (N/A - no previous sum)\n\
+            B: 618 (increased)\n\
+            C: 618 (no change)\n\
+            D: 617 (decreased)\n\
+            E: 647 (increased)\n\
+            F: 716 (increased)\n\
+            G: 769 (increased)\n\
+            H: 792 (increased)\n\
+            \n\
+            increased: 5\n\
+            decreased: 1\n\
+            no change: 1\n");
+
+        assert!(challenge.set_param("mode", "verbose").is_err());
+    }
+
+    #[test]
+    fn test_profile() {
+        let profile = Profile::new(Readings::new(data(), Policy::Strict));
+        assert_eq!(profile.longest_run(Ordering::Greater), Some(Run { start: 0, end: 3 }));
+        assert_eq!(profile.longest_run(Ordering::Less), Some(Run { start: 3, end: 4 }));
+        assert_eq!(profile.largest_drop(), Some(Run { start: 3, end: 4 }));
+
+        let text = profile.to_string();
+        assert!(text.starts_with("measurements: 10\nmin depth: 199 (line 1)\nmax depth: 269 (line 8)\nmean depth: 225.6\n"));
+        assert!(text.contains("largest drop: 10, lines 4-5 (210 -> 200)\n"));
+        assert!(text.contains("\n199 |##  #\n"));
+        assert!(text.ends_with("269 |##########\n    +----------\n     1       10\n"));
+
+        let flat = Profile::new(vec![(1, 5), (2, 5), (3, 5)]);
+        assert_eq!(flat.longest_run(Ordering::Greater), None);
+        assert_eq!(flat.largest_drop(), None);
+        assert_eq!(Profile::new(Vec::new()).to_string(), "no measurements\n");
+    }
+
+    // A sweep with a bad line, a blank one and a bad one at the end
+    fn glitched() -> Vec<String> {
+        ["199", "200", "oops", "", "210", "200", "x"].iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_strict() {
+        assert_eq!(challenges::rejection(|| Day1::new().part_1(glitched())), "line 3: invalid depth `oops`");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_skip_and_interpolate() {
+        let mut challenge = Day1::new();
+        challenge.set_param("parse", "skip").unwrap();
+        assert_eq!(challenge.part_1(glitched()), "\
+            2\n\
+            \n\
+            skipped 3 lines:\n\
+            line 3: invalid depth `oops`\n\
+            line 4: blank\n\
+            line 7: invalid depth `x`\n");
+
+        let readings = Readings::new(glitched(), Policy::Interpolate).collect::<Vec<_>>();
+        assert_eq!(readings, vec![(1, 199), (2, 200), (3, 203), (4, 207), (5, 210), (6, 200), (7, 200)]);
+
+        challenge.set_param("parse", "interpolate").unwrap();
+        assert!(challenge.part_1(glitched()).starts_with("4\n\ninterpolated 3 lines:\nline 3: invalid depth `oops` (filled in as 203)\n"));
+
+        // Line numbers in the analysis still match the input
+        challenge.set_param("mode", "analyze").unwrap();
+        challenge.set_param("parse", "skip").unwrap();
+        assert!(challenge.part_1(glitched()).contains("longest decrease: 2 measurements, lines 5-6 (210 -> 200)\n"));
+        assert!(challenge.set_param("parse", "lenient").is_err());
+    }
+
+    #[test]
+    fn test_window_label() {
+        let labels = [0, 25, 26, 27, 701, 702].iter().map(|&i| window_label(i)).collect::<Vec<_>>();
+        assert_eq!(labels, vec!["A", "Z", "AA", "AB", "ZZ", "AAA"]);
+    }
+}
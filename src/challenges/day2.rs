@@ -1,59 +1,296 @@
-use std::convert::From;
-use crate::challenges::Challenge;
+mod constraints;
+mod plan;
+mod script;
+mod trajectory;
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use crate::challenges::{self, Challenge};
+use crate::geometry::{Point, Vector};
+use constraints::{Constraints, NavigationError, Policy, StepError};
+use script::Script;
+use trajectory::{Export, Trajectory};
 
 /// Submarine travelling direction (submarines cannot travel
 /// backwards... apparently)
+#[derive(Clone, Copy, Debug, PartialEq)]
 enum Direction {
     Up(u32),
     Down(u32),
     Forward(u32),
 }
 
-impl From<String> for Direction {
-    fn from(text: String) -> Self {
-        // Submarine instructions come in the form "forward 10", with an
-        // instruction and distance separated by a space
-        let mut parts = text.split(" ");
+/// Why a direction couldn't be parsed
+#[derive(Clone, Debug, PartialEq)]
+enum ParseError {
+    Empty,
+    UnknownCommand(String),
+    MissingDistance(String),
+    InvalidDistance(String),
+
+    /// Whitespace before or after a strictly written direction
+    Whitespace,
+
+    /// Anything left over after a strictly written direction
+    Unexpected(String),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "no direction"),
+            ParseError::UnknownCommand(command) => write!(f, "unknown command `{}`", command),
+            ParseError::MissingDistance(command) => write!(f, "`{}` needs a distance", command),
+            ParseError::InvalidDistance(distance) => write!(f, "invalid distance `{}`", distance),
+            ParseError::Whitespace => write!(f, "leading or trailing whitespace"),
+            ParseError::Unexpected(text) => write!(f, "unexpected `{}`", text),
+        }
+    }
+}
+
+/// How closely directions have to match the puzzle's format
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Strictness {
+    /// Exactly as in the puzzle: `forward 10`, with a single space
+    Strict,
+
+    /// Any case and any whitespace, with commands shortened to their first
+    /// letter and distances written straight after them allowed, like
+    /// `F 10` or `f10`. A line can hold any number of directions.
+    Lenient,
+}
 
-        // Match the instruction and the result of parsing the second
-        // part from the split to create a `Direction`, panicking on anything
-        // that doesn't match
-        match (parts.next(), parts.next().and_then(|num| num.parse::<u32>().ok())) {
-            (Some("up"), Some(n @ _)) => Direction::Up(n),
-            (Some("down"), Some(n @ _)) => Direction::Down(n),
-            (Some("forward"), Some(n @ _)) => Direction::Forward(n),
-            _ => panic!("malformed direction: {}", text),
+impl Strictness {
+    fn parse(value: &str) -> Result<Strictness, String> {
+        match value {
+            "strict" => Ok(Strictness::Strict),
+            "lenient" => Ok(Strictness::Lenient),
+            _ => Err(format!("invalid parse mode: {}", value)),
         }
     }
 }
 
-/// Position of a submarine both laterally through the water, and its depth
+fn parse_distance(text: &str) -> Result<u32, ParseError> {
+    match text.parse::<u32>() {
+        Ok(n) if text.bytes().all(|b| b.is_ascii_digit()) => Ok(n),
+        _ => Err(ParseError::InvalidDistance(String::from(text))),
+    }
+}
+
+impl Direction {
+    fn constructor(command: &str) -> Option<fn(u32) -> Direction> {
+        match command {
+            "up" => Some(Direction::Up),
+            "down" => Some(Direction::Down),
+            "forward" => Some(Direction::Forward),
+            _ => None,
+        }
+    }
+
+    /// Parse a line of the input into the directions on it. Strictly,
+    /// that's always exactly one.
+    fn parse_line(line: &str, strictness: Strictness) -> Result<Vec<Direction>, ParseError> {
+        if strictness == Strictness::Strict {
+            return Ok(vec![line.parse()?]);
+        }
+
+        let line = line.to_lowercase();
+        let mut words = line.split_whitespace();
+        let mut directions = Vec::new();
+
+        while let Some(word) = words.next() {
+            // The command is the letters at the start of the word, and the
+            // distance is either the rest of it or the next word
+            let split = word.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(word.len());
+            let (command, distance) = word.split_at(split);
+
+            let constructor = match command {
+                "u" => Direction::constructor("up"),
+                "d" => Direction::constructor("down"),
+                "f" => Direction::constructor("forward"),
+                _ => Direction::constructor(command),
+            };
+            let constructor = constructor
+                .ok_or_else(|| ParseError::UnknownCommand(String::from(command)))?;
+
+            let distance = match distance {
+                "" => words.next().ok_or_else(|| ParseError::MissingDistance(String::from(command)))?,
+                distance => distance,
+            };
+            directions.push(constructor(parse_distance(distance)?));
+        }
+
+        Ok(directions)
+    }
+}
+
+/// Parse a direction written exactly as in the puzzle, like "forward 10",
+/// with an instruction and distance separated by a single space
+impl FromStr for Direction {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        if text.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        if text.trim() != text {
+            return Err(ParseError::Whitespace);
+        }
+
+        let (command, distance) = text.split_once(' ')
+            .ok_or_else(|| match Direction::constructor(text) {
+                Some(_) => ParseError::MissingDistance(String::from(text)),
+                None => ParseError::UnknownCommand(String::from(text)),
+            })?;
+
+        let constructor = Direction::constructor(command)
+            .ok_or_else(|| ParseError::UnknownCommand(String::from(command)))?;
+
+        match distance.split_once(' ') {
+            Some((number, rest)) if !number.is_empty() => Err(ParseError::Unexpected(String::from(rest))),
+            _ => Ok(constructor(parse_distance(distance)?)),
+        }
+    }
+}
+
+/// Directions are written as they appear in the input
+impl Display for Direction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::Up(n) => write!(f, "up {}", n),
+            Direction::Down(n) => write!(f, "down {}", n),
+            Direction::Forward(n) => write!(f, "forward {}", n),
+        }
+    }
+}
+
+impl Direction {
+    /// How far the instruction moves the submarine, taken literally: `x` is
+    /// the horizontal position and `y` the depth, which grows downwards
+    fn vector(&self) -> Vector {
+        match *self {
+            Direction::Up(distance) => Vector::new(0, -(distance as i64)),
+            Direction::Down(distance) => Vector::new(0, distance as i64),
+            Direction::Forward(distance) => Vector::new(distance as i64, 0),
+        }
+    }
+}
+
+/// A way of interpreting the submarine's instructions
+trait NavigationModel {
+    /// Use a direction instruction to move the submarine, within the
+    /// constraints
+    fn go(&mut self, direction: &Direction, constraints: &Constraints) -> Result<(), NavigationError>;
+
+    /// The puzzle's answer for where the submarine has got to
+    fn answer(&self) -> Result<i64, String>;
+
+    /// The model as a position on a chart, for models that move through
+    /// horizontal position and depth
+    fn charted(&self) -> Option<&dyn Charted> {
+        None
+    }
+
+    /// The model's aim, for models where that matters
+    fn aimed(&self) -> Option<&dyn Aimed> {
+        None
+    }
+
+    /// The model as a course planner, for models that know how to find one
+    fn planner(&self) -> Option<&dyn Planner> {
+        None
+    }
+}
+
+/// A model whose position is a horizontal position (`x`) and depth (`y`)
+trait Charted {
+    fn location(&self) -> Point;
+}
+
+/// A model that keeps track of which way the submarine is pointing
+trait Aimed {
+    fn aim(&self) -> i64;
+}
+
+/// A model that can work out courses to follow
+trait Planner {
+    /// The shortest course from the start to `target`
+    fn plan(&self, target: Point) -> Result<Vec<Direction>, String>;
+}
+
+type Constructor = fn() -> Box<dyn NavigationModel>;
+
+/// Every navigation model, by name
+const MODELS: &[(&str, Constructor)] = &[
+    ("position", || Box::new(Position::new())),
+    ("aim", || Box::new(Attitude::new())),
+    ("deepest", || Box::new(Deepest::new())),
+];
+
+fn new_model(name: &str) -> Result<Box<dyn NavigationModel>, String> {
+    MODELS.iter()
+        .find(|(model, _)| *model == name)
+        .map(|(_, constructor)| constructor())
+        .ok_or_else(|| format!("unknown navigation model: {}", name))
+}
+
+/// Multiply together a horizontal position and depth
+fn product(x: i64, y: i64) -> Result<i64, String> {
+    x.checked_mul(y).ok_or_else(|| format!("answer overflowed: {} * {}", x, y))
+}
+
+/// Position of a submarine both laterally through the water (`x`), and its
+/// depth (`y`)
 struct Position {
-    horizontal: i32,
-    depth: i32,
+    location: Point,
 }
 
 impl Position {
     fn new() -> Self {
         Position {
-            horizontal: 0,
-            depth: 0,
+            location: Point::ORIGIN,
         }
     }
+}
 
-    /// Use a direction instruction to modify the submarine's position
-    fn go(&mut self, direction: Direction) {
-        match direction {
-            Direction::Up(distance) => self.depth -= distance as i32,
-            Direction::Down(distance) => self.depth += distance as i32,
-            Direction::Forward(distance) => self.horizontal += distance as i32,
-        }
+impl NavigationModel for Position {
+    /// Each instruction moves the submarine directly
+    fn go(&mut self, direction: &Direction, constraints: &Constraints) -> Result<(), NavigationError> {
+        let location = self.location.checked_add(direction.vector())
+            .ok_or(NavigationError::Overflow)?;
+
+        self.location = Point::new(location.x, constraints.depth(location.y)?);
+        Ok(())
+    }
+
+    fn answer(&self) -> Result<i64, String> {
+        product(self.location.x, self.location.y)
+    }
+
+    fn charted(&self) -> Option<&dyn Charted> {
+        Some(self)
+    }
+
+    fn planner(&self) -> Option<&dyn Planner> {
+        Some(self)
+    }
+}
+
+impl Charted for Position {
+    fn location(&self) -> Point {
+        self.location
+    }
+}
+
+impl Planner for Position {
+    fn plan(&self, target: Point) -> Result<Vec<Direction>, String> {
+        plan::direct(target)
     }
 }
 
 /// The position and aim of the submarine
 struct Attitude {
-    aim: i32,
+    aim: i64,
     position: Position,
 }
 
@@ -64,90 +301,449 @@ impl Attitude {
             position: Position::new(),
         }
     }
+}
 
-    /// Use a direction instruction to modify the submarine's position -
-    /// this is different to `Position::go` in that the submarine's depth
-    /// changes depending on its aim
-    fn go(&mut self, direction: Direction) {
-        match direction {
-            Direction::Up(amount) => self.aim -= amount as i32,
-            Direction::Down(amount) => self.aim += amount as i32,
+impl NavigationModel for Attitude {
+    /// This is different to `Position::go` in that up and down only change
+    /// the submarine's aim, and its depth changes depending on its aim as it
+    /// moves forward
+    fn go(&mut self, direction: &Direction, constraints: &Constraints) -> Result<(), NavigationError> {
+        match *direction {
+            Direction::Up(amount) => {
+                let aim = self.aim.checked_sub(amount as i64).ok_or(NavigationError::Overflow)?;
+                self.aim = constraints.aim(aim)?;
+            },
+            Direction::Down(amount) => {
+                let aim = self.aim.checked_add(amount as i64).ok_or(NavigationError::Overflow)?;
+                self.aim = constraints.aim(aim)?;
+            },
             Direction::Forward(distance) => {
-                self.position.horizontal += distance as i32;
-                self.position.depth += self.aim * distance as i32;
+                let distance = distance as i64;
+                let location = self.aim.checked_mul(distance)
+                    .and_then(|dive| self.position.location.checked_add(Vector::new(distance, dive)))
+                    .ok_or(NavigationError::Overflow)?;
+
+                self.position.location = Point::new(location.x, constraints.depth(location.y)?);
             }
         }
+
+        Ok(())
+    }
+
+    fn answer(&self) -> Result<i64, String> {
+        self.position.answer()
+    }
+
+    fn charted(&self) -> Option<&dyn Charted> {
+        Some(&self.position)
+    }
+
+    fn aimed(&self) -> Option<&dyn Aimed> {
+        Some(self)
+    }
+
+    fn planner(&self) -> Option<&dyn Planner> {
+        Some(self)
+    }
+}
+
+impl Aimed for Attitude {
+    fn aim(&self) -> i64 {
+        self.aim
     }
 }
 
-pub struct Day2 {}
+impl Planner for Attitude {
+    fn plan(&self, target: Point) -> Result<Vec<Direction>, String> {
+        plan::aimed(target)
+    }
+}
+
+/// Moves like `Position`, but remembers the deepest the submarine has been
+struct Deepest {
+    deepest: i64,
+    position: Position,
+}
+
+impl Deepest {
+    fn new() -> Self {
+        Deepest {
+            deepest: 0,
+            position: Position::new(),
+        }
+    }
+}
+
+impl NavigationModel for Deepest {
+    fn go(&mut self, direction: &Direction, constraints: &Constraints) -> Result<(), NavigationError> {
+        self.position.go(direction, constraints)?;
+        self.deepest = self.deepest.max(self.position.location.y);
+        Ok(())
+    }
+
+    /// The final horizontal position multiplied by the greatest depth
+    fn answer(&self) -> Result<i64, String> {
+        product(self.position.location.x, self.deepest)
+    }
+
+    fn charted(&self) -> Option<&dyn Charted> {
+        Some(&self.position)
+    }
+}
+
+/// How the course is written in the input
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    /// One direction per line, as in the puzzle
+    Commands,
+
+    /// The course language in `script`
+    Script,
+}
+
+impl Format {
+    fn parse(value: &str) -> Result<Format, String> {
+        match value {
+            "commands" => Ok(Format::Commands),
+            "script" => Ok(Format::Script),
+            _ => Err(format!("invalid input format: {}", value)),
+        }
+    }
+}
+
+/// Write out directions in the same format as the input
+fn course(directions: &[Direction]) -> String {
+    directions.iter()
+        .map(|direction| format!("{}\n", direction))
+        .collect()
+}
+
+fn parse_number(name: &str, value: &str) -> Result<i64, String> {
+    value.parse::<i64>()
+        .map_err(|_| format!("invalid value for `{}`: {}", name, value))
+}
+
+fn parse_flag(name: &str, value: &str) -> Result<bool, String> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("invalid value for `{}`: {}", name, value)),
+    }
+}
+
+pub struct Day2 {
+    /// Name of the navigation model to use, overriding the puzzle's
+    /// `position` and `aim`
+    model: Option<String>,
+
+    /// Give the whole course in this format instead of the answer
+    trajectory: Option<Export>,
+    format: Format,
+
+    /// Give the flattened list of directions instead of the answer
+    expand: bool,
+
+    /// Ignore the input and give the shortest course to this target instead
+    plan: Option<Point>,
+    constraints: Constraints,
+
+    /// How closely the input has to match the puzzle's format
+    strictness: Strictness,
+}
 
 impl Day2 {
     pub fn new() -> Day2 {
-        Day2 {}
+        Day2 {
+            model: None,
+            trajectory: None,
+            format: Format::Commands,
+            expand: false,
+            plan: None,
+            constraints: Constraints::new(),
+            strictness: Strictness::Strict,
+        }
     }
-}
 
-impl Challenge for Day2 {
-    fn part_1(&self, input: Vec<String>) -> String {
-        let final_position = input.into_iter()
+    fn directions(&self, input: Vec<String>) -> Vec<Direction> {
+        match self.format {
+            Format::Commands => input.iter().enumerate()
+                .flat_map(|(i, line)| Direction::parse_line(line, self.strictness)
+                    .unwrap_or_else(|err| challenges::invalid_input(format!("line {}: {}", i + 1, err))))
+                .collect(),
+            Format::Script => Script::parse(&input.join("\n"))
+                .and_then(|script| script.expand())
+                .unwrap_or_else(|err| challenges::invalid_input(format!("invalid script: {}", err))),
+        }
+    }
+
+    /// Follow every instruction in the input with the selected model, and
+    /// give its answer
+    fn navigate(&self, input: Vec<String>, default: &str) -> String {
+        let mut model = new_model(self.model.as_deref().unwrap_or(default))
+            .unwrap_or_else(|err| panic!("{}", err));
+
+        if let Some(target) = self.plan {
+            let planner = model.planner().unwrap_or_else(|| panic!("this navigation model can't plan courses"));
+            let planned = planner.plan(target).unwrap_or_else(|err| panic!("{}", err));
+            return course(&planned);
+        }
+
+        let directions = self.directions(input);
+        if self.expand {
+            return course(&directions);
+        }
+
+        if let Some(format) = self.trajectory {
+            if model.charted().is_none() {
+                panic!("this navigation model can't record a trajectory");
+            }
+            let trajectory = Trajectory::record(model.as_mut(), directions, &self.constraints)
+                .unwrap_or_else(|err| challenges::invalid_input(err));
+            return format.export(&trajectory);
+        }
 
-            // Parse each direction instruction from the input
-            .map(|line| Direction::from(line))
+        for (i, direction) in directions.into_iter().enumerate() {
+            model.go(&direction, &self.constraints)
+                .unwrap_or_else(|error| challenges::invalid_input(StepError { step: i + 1, direction, error }));
+        }
 
-            // Execute each instruction to calculate the submarine's final
-            // position in the water
-            .fold(Position::new(), |mut acc, x| {
-                acc.go(x);
-                acc
-            });
+        model.answer().unwrap_or_else(|err| panic!("{}", err)).to_string()
+    }
+}
 
-        (final_position.horizontal * final_position.depth).to_string()
+impl Challenge for Day2 {
+    fn part_1(&self, input: Vec<String>) -> String {
+        self.navigate(input, "position")
     }
 
     fn part_2(&self, input: Vec<String>) -> String {
-        let final_att = input.into_iter()
-
-            // Parse each direction instruction from the input
-            .map(|line| Direction::from(line))
+        self.navigate(input, "aim")
+    }
 
-            // Execute each instruction using the attitude method to calculate
-            // the submarine's final position in the water
-            .fold(Attitude::new(), |mut acc, x| {
-                acc.go(x);
-                acc
-            });
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name {
+            "model" => {
+                new_model(value)?;
+                self.model = Some(String::from(value));
+            },
+            "trajectory" => self.trajectory = Some(Export::parse(value)?),
+            "input" => self.format = Format::parse(value)?,
+            "expand" => self.expand = parse_flag(name, value)?,
+            "plan" => self.plan = Some(value.parse()?),
+            "surface" => self.constraints.surface = parse_flag(name, value)?,
+            "max_depth" => self.constraints.max_depth = Some(parse_number(name, value)?),
+            "min_aim" => self.constraints.min_aim = Some(parse_number(name, value)?),
+            "max_aim" => self.constraints.max_aim = Some(parse_number(name, value)?),
+            "constraints" => self.constraints.policy = Policy::parse(value)?,
+            "parse" => self.strictness = Strictness::parse(value)?,
+            _ => return Err(format!("unknown parameter `{}`", name)),
+        }
 
-        (final_att.position.horizontal * final_att.position.depth).to_string()
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::challenges::input_from_str;
+    use crate::challenges::{self, input_from_str};
 
     // Example data from the challenge description
-    const DATA: &'static str = r"
-        forward 5
-        down 5
-        forward 8
-        up 3
-        down 8
-        forward 2
-    ";
+    fn data() -> Vec<String> {
+        input_from_str(&challenges::example(2).input)
+    }
 
     #[test]
     fn test_part_1() {
-        let input = input_from_str(DATA);
         let challenge = super::Day2::new();
-        assert_eq!(challenge.part_1(input), "150");
+        assert_eq!(Some(challenge.part_1(data())), challenges::example(2).answers[0]);
     }
 
     #[test]
     fn test_part_2() {
-        let input = input_from_str(DATA);
         let challenge = super::Day2::new();
-        assert_eq!(challenge.part_2(input), "900");
+        assert_eq!(Some(challenge.part_2(data())), challenges::example(2).answers[1]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_models() {
+        let mut challenge = super::Day2::new();
+        challenge.set_param("model", "aim").unwrap();
+        assert_eq!(challenge.part_1(data()), "900");
+
+        challenge.set_param("model", "position").unwrap();
+        assert_eq!(challenge.part_2(data()), "150");
+
+        assert_eq!(challenge.set_param("model", "3d"), Err(String::from("unknown navigation model: 3d")));
+    }
+
+    #[test]
+    fn test_deepest() {
+        let mut challenge = super::Day2::new();
+        challenge.set_param("model", "deepest").unwrap();
+        assert_eq!(challenge.part_1(input_from_str("down 5\nforward 2\nup 3")), "10");
+        assert_eq!(challenge.part_2(data()), "150");
+
+        challenge.set_param("trajectory", "csv").unwrap();
+        assert!(challenge.part_1(data()).ends_with("6,forward 2,15,10,0\n"));
+
+        challenge.set_param("plan", "15,10").unwrap();
+        let result = std::panic::catch_unwind(|| challenge.part_1(Vec::new()));
+        assert_eq!(*result.unwrap_err().downcast::<&str>().unwrap(), "this navigation model can't plan courses");
+    }
+
+    #[test]
+    fn test_trajectory() {
+        let mut model = new_model("aim").unwrap();
+        let directions = data().into_iter().map(|line| line.parse().unwrap());
+        let trajectory = Trajectory::record(model.as_mut(), directions, &Constraints::new()).unwrap();
+
+        let csv = trajectory.to_csv();
+        assert!(csv.starts_with("step,command,horizontal,depth,aim\n0,,0,0,0\n1,forward 5,5,0,0\n2,down 5,5,0,5\n"));
+
+        let json = serde_json::from_str::<serde_json::Value>(&trajectory.to_json()).unwrap();
+        assert_eq!(json[0]["command"], serde_json::Value::Null);
+        assert_eq!(json[3], serde_json::json!({ "step": 3, "command": "forward 8", "horizontal": 13, "depth": 40, "aim": 5 }));
+        assert_eq!(json[6], serde_json::json!({ "step": 6, "command": "forward 2", "horizontal": 15, "depth": 60, "aim": 10 }));
+
+        let svg = trajectory.to_svg();
+        assert!(svg.starts_with("<svg") && svg.contains("<polyline"));
+    }
+
+    #[test]
+    fn test_trajectory_plot() {
+        let mut challenge = super::Day2::new();
+        challenge.set_param("trajectory", "ascii").unwrap();
+        assert_eq!(challenge.part_1(data()), "\
+            \x200 |######\n\
+            \x20  |     #\n\
+            \x20  |     #       #\n\
+            \x20  |     #       #\n\
+            \x20  |     #       #\n\
+            \x20  |     #########\n\
+            \x20  |             #\n\
+            \x20  |             #\n\
+            \x20  |             #\n\
+            \x20  |             #\n\
+            10 |             ###\n\
+            \x20  +----------------\n\
+            \x20   0             15\n");
+
+        assert!(challenge.set_param("trajectory", "png").is_err());
+    }
+
+    #[test]
+    fn test_script() {
+        let mut challenge = super::Day2::new();
+        challenge.set_param("input", "script").unwrap();
+
+        // The example course, written with a loop
+        let input = input_from_str("forward 5 down 5 forward 8 up 3 # first half\nlet n = 2\nrepeat n { down 4 }\nforward 2");
+        assert_eq!(challenge.part_2(input.clone()), "900");
+
+        challenge.set_param("expand", "true").unwrap();
+        assert_eq!(challenge.part_1(input), "forward 5\ndown 5\nforward 8\nup 3\ndown 4\ndown 4\nforward 2\n");
+        assert!(challenge.set_param("expand", "yes").is_err());
+    }
+
+    #[test]
+    fn test_plan() {
+        let mut challenge = super::Day2::new();
+        challenge.set_param("plan", "15,10").unwrap();
+        let planned = challenge.part_1(Vec::new());
+        assert_eq!(planned, "forward 15\ndown 10\n");
+
+        // Planned courses are valid input for the same model
+        let mut challenge = super::Day2::new();
+        assert_eq!(challenge.part_1(input_from_str(&planned)), "150");
+
+        challenge.set_param("plan", "10,4").unwrap();
+        let planned = challenge.part_2(Vec::new());
+        assert_eq!(planned, "forward 8\ndown 2\nforward 2\n");
+        assert_eq!(super::Day2::new().part_2(input_from_str(&planned)), "40");
+
+        assert!(challenge.set_param("plan", "10").is_err());
+    }
+
+    #[test]
+    fn test_constraints() {
+        let mut challenge = super::Day2::new();
+        challenge.set_param("max_depth", "50").unwrap();
+        assert_eq!(challenge.part_1(data()), "150");
+
+        // The aim model reaches depth 60 on the last step
+        assert_eq!(challenges::rejection(|| challenge.part_2(data())),
+            "step 6 (forward 2): depth 60 is below the maximum of 50");
+
+        challenge.set_param("constraints", "clamp").unwrap();
+        assert_eq!(challenge.part_2(data()), "750");
+
+        let mut constraints = Constraints::new();
+        constraints.surface = true;
+        constraints.max_aim = Some(3);
+        assert_eq!(constraints.depth(-1), Err(NavigationError::AboveSurface(-1)));
+        assert_eq!(constraints.aim(2), Ok(2));
+        assert_eq!(constraints.aim(4).unwrap_err().to_string(), "aim 4 is above the maximum of 3");
+        constraints.min_aim = Some(-2);
+        assert_eq!(constraints.aim(-5).unwrap_err().to_string(), "aim -5 is below the minimum of -2");
+
+        constraints.policy = Policy::Clamp;
+        assert_eq!((constraints.depth(-1), constraints.aim(4)), (Ok(0), Ok(3)));
+
+        assert!(challenge.set_param("max_depth", "deep").is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let mut model = new_model("aim").unwrap();
+        let constraints = Constraints::new();
+        model.go(&Direction::Down(u32::MAX), &constraints).unwrap();
+        model.go(&Direction::Forward(1 << 31), &constraints).unwrap();
+        assert_eq!(model.go(&Direction::Forward(1), &constraints), Err(NavigationError::Overflow));
+
+        let mut model = new_model("position").unwrap();
+        for _ in 0..3 {
+            model.go(&Direction::Down(u32::MAX), &constraints).unwrap();
+        }
+        assert_eq!(model.charted().unwrap().location(), Point::new(0, 3 * u32::MAX as i64));
+    }
+
+    #[test]
+    fn test_parse_strict() {
+        assert_eq!("forward 10".parse(), Ok(Direction::Forward(10)));
+        assert_eq!("".parse::<Direction>(), Err(ParseError::Empty));
+        assert_eq!("sideways 2".parse::<Direction>(), Err(ParseError::UnknownCommand(String::from("sideways"))));
+        assert_eq!("down".parse::<Direction>(), Err(ParseError::MissingDistance(String::from("down"))));
+        assert_eq!("up +3".parse::<Direction>(), Err(ParseError::InvalidDistance(String::from("+3"))));
+        assert_eq!("up  3".parse::<Direction>(), Err(ParseError::InvalidDistance(String::from(" 3"))));
+        assert_eq!("up 3 4".parse::<Direction>(), Err(ParseError::Unexpected(String::from("4"))));
+        assert_eq!("forward 10 ".parse::<Direction>().unwrap_err().to_string(), "leading or trailing whitespace");
+        assert_eq!(" down 2".parse::<Direction>(), Err(ParseError::Whitespace));
+        assert_eq!("Forward 1".parse::<Direction>().unwrap_err().to_string(), "unknown command `Forward`");
+
+        // Directions write out in the format they're parsed from
+        for text in ["up 3", "down 0", "forward 4294967295"] {
+            assert_eq!(text.parse::<Direction>().unwrap().to_string(), text);
+        }
+    }
+
+    #[test]
+    fn test_parse_lenient() {
+        let parse = |line| Direction::parse_line(line, Strictness::Lenient);
+        assert_eq!(parse("  FORWARD\t5 "), Ok(vec![Direction::Forward(5)]));
+        assert_eq!(parse("f5 d3 u2"), Ok(vec![Direction::Forward(5), Direction::Down(3), Direction::Up(2)]));
+        assert_eq!(parse("Down 4  u 1"), Ok(vec![Direction::Down(4), Direction::Up(1)]));
+        assert_eq!(parse(""), Ok(Vec::new()));
+        assert_eq!(parse("f5 b2"), Err(ParseError::UnknownCommand(String::from("b"))));
+        assert_eq!(parse("f5 d"), Err(ParseError::MissingDistance(String::from("d"))));
+        assert_eq!(parse("f5x"), Err(ParseError::InvalidDistance(String::from("5x"))));
+
+        let mut challenge = super::Day2::new();
+        challenge.set_param("parse", "lenient").unwrap();
+        assert_eq!(challenge.part_2(input_from_str("f5 D5 f8\nUP 3\ndown\t8\nf2")), "900");
+
+        assert_eq!(challenges::rejection(|| super::Day2::new().part_1(input_from_str("forward 5\nf5"))),
+            "line 2: unknown command `f5`");
+        assert!(challenge.set_param("parse", "loose").is_err());
+    }
+}
@@ -0,0 +1,114 @@
+use std::fmt::{self, Display, Formatter};
+
+use super::Direction;
+
+/// What happens when the submarine would break one of its constraints
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Policy {
+    /// Stop the course with an error
+    Report,
+
+    /// Hold the submarine at the limit and carry on
+    Clamp,
+}
+
+impl Policy {
+    pub fn parse(value: &str) -> Result<Policy, String> {
+        match value {
+            "report" => Ok(Policy::Report),
+            "clamp" => Ok(Policy::Clamp),
+            _ => Err(format!("invalid constraint policy: {}", value)),
+        }
+    }
+}
+
+/// Why the submarine couldn't follow a direction
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NavigationError {
+    Overflow,
+    AboveSurface(i64),
+    TooDeep { depth: i64, max: i64 },
+    AimTooLow { aim: i64, min: i64 },
+    AimTooHigh { aim: i64, max: i64 },
+}
+
+impl Display for NavigationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            NavigationError::Overflow => write!(f, "position or aim overflowed"),
+            NavigationError::AboveSurface(depth) => write!(f, "depth {} is above the surface", depth),
+            NavigationError::TooDeep { depth, max } => write!(f, "depth {} is below the maximum of {}", depth, max),
+            NavigationError::AimTooLow { aim, min } => write!(f, "aim {} is below the minimum of {}", aim, min),
+            NavigationError::AimTooHigh { aim, max } => write!(f, "aim {} is above the maximum of {}", aim, max),
+        }
+    }
+}
+
+/// A navigation error, with the step of the course it happened at
+#[derive(Debug, PartialEq)]
+pub struct StepError {
+    /// Counting from 1
+    pub step: usize,
+    pub direction: Direction,
+    pub error: NavigationError,
+}
+
+impl Display for StepError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "step {} ({}): {}", self.step, self.direction, self.error)
+    }
+}
+
+/// Limits on where the submarine can go. There are none unless they're
+/// asked for, as the puzzle doesn't have any.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Constraints {
+    /// Whether the submarine has to stay below the surface, at depth 0
+    pub surface: bool,
+    pub max_depth: Option<i64>,
+    pub min_aim: Option<i64>,
+    pub max_aim: Option<i64>,
+    pub policy: Policy,
+}
+
+impl Constraints {
+    pub fn new() -> Self {
+        Constraints {
+            surface: false,
+            max_depth: None,
+            min_aim: None,
+            max_aim: None,
+            policy: Policy::Report,
+        }
+    }
+
+    /// Check a depth the submarine is about to move to, giving the depth it
+    /// actually moves to
+    pub fn depth(&self, depth: i64) -> Result<i64, NavigationError> {
+        if self.surface && depth < 0 {
+            return self.violate(0, NavigationError::AboveSurface(depth));
+        }
+
+        match self.max_depth {
+            Some(max) if depth > max => self.violate(max, NavigationError::TooDeep { depth, max }),
+            _ => Ok(depth),
+        }
+    }
+
+    /// Check an aim the submarine is about to take, giving the aim it
+    /// actually takes
+    pub fn aim(&self, aim: i64) -> Result<i64, NavigationError> {
+        match (self.min_aim, self.max_aim) {
+            (Some(min), _) if aim < min => self.violate(min, NavigationError::AimTooLow { aim, min }),
+            (_, Some(max)) if aim > max => self.violate(max, NavigationError::AimTooHigh { aim, max }),
+            _ => Ok(aim),
+        }
+    }
+
+    fn violate(&self, limit: i64, error: NavigationError) -> Result<i64, NavigationError> {
+        match self.policy {
+            Policy::Report => Err(error),
+            Policy::Clamp => Ok(limit),
+        }
+    }
+}
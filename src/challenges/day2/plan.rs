@@ -0,0 +1,116 @@
+//! Planning courses: the shortest list of directions that takes the
+//! submarine from the start to a target horizontal position and depth.
+
+use std::convert::TryFrom;
+
+use crate::geometry::Point;
+
+use super::Direction;
+
+fn distance(value: i64, target: Point) -> Result<u32, String> {
+    u32::try_from(value.abs()).map_err(|_| format!("can't reach {}: a step is too far", target))
+}
+
+/// Move up or down by `amount`, depending on its sign
+fn vertical(amount: i64, target: Point) -> Result<Direction, String> {
+    let distance = distance(amount, target)?;
+    Ok(if amount < 0 { Direction::Up(distance) } else { Direction::Down(distance) })
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
+
+/// Where each direction moves the submarine directly, it takes one for
+/// each axis the target is away from the start
+pub fn direct(target: Point) -> Result<Vec<Direction>, String> {
+    if target.x < 0 {
+        return Err(format!("can't reach {}: submarines can't go backwards", target));
+    }
+
+    let mut course = Vec::new();
+    if target.x > 0 {
+        course.push(Direction::Forward(distance(target.x, target)?));
+    }
+    if target.y != 0 {
+        course.push(vertical(target.y, target)?);
+    }
+
+    Ok(course)
+}
+
+/// Where up and down only change the aim, the depth only changes while
+/// moving forward, by the aim for each step. Two moves forward at different
+/// aims are enough to reach any depth: move most of the way flat, then aim
+/// and cover the depth in the last `g` steps, where `g` divides both the
+/// distance and the depth. When the whole distance does that, the first
+/// move isn't needed.
+pub fn aimed(target: Point) -> Result<Vec<Direction>, String> {
+    match (target.x, target.y) {
+        (x, _) if x < 0 => Err(format!("can't reach {}: submarines can't go backwards", target)),
+        (0, 0) => Ok(Vec::new()),
+        (0, _) => Err(format!("can't reach {}: depth only changes moving forward", target)),
+        (x, 0) => Ok(vec![Direction::Forward(distance(x, target)?)]),
+        (x, y) => {
+            let last = gcd(x, y.abs());
+
+            let mut course = Vec::new();
+            if last < x {
+                course.push(Direction::Forward(distance(x - last, target)?));
+            }
+            course.push(vertical(y / last, target)?);
+            course.push(Direction::Forward(distance(last, target)?));
+
+            Ok(course)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::challenges::day2::new_model;
+    use crate::challenges::day2::constraints::Constraints;
+
+    /// Follow a planned course, checking it ends up at the target
+    fn round_trip(model: &str, target: Point) -> usize {
+        let mut model = new_model(model).unwrap();
+        let course = model.planner().unwrap().plan(target).unwrap();
+
+        for direction in &course {
+            model.go(direction, &Constraints::new()).unwrap();
+        }
+        assert_eq!(model.charted().unwrap().location(), target);
+
+        course.len()
+    }
+
+    #[test]
+    fn test_direct() {
+        assert_eq!(direct(Point::new(15, 10)), Ok(vec![Direction::Forward(15), Direction::Down(10)]));
+        assert_eq!(round_trip("position", Point::new(0, -4)), 1);
+        assert_eq!(round_trip("position", Point::ORIGIN), 0);
+        assert!(direct(Point::new(-1, 0)).is_err());
+    }
+
+    #[test]
+    fn test_aimed() {
+        assert_eq!(aimed(Point::new(15, 60)), Ok(vec![Direction::Down(4), Direction::Forward(15)]));
+        assert_eq!(aimed(Point::new(10, 4)), Ok(vec![Direction::Forward(8), Direction::Down(2), Direction::Forward(2)]));
+        assert_eq!(aimed(Point::new(7, -3)), Ok(vec![Direction::Forward(6), Direction::Up(3), Direction::Forward(1)]));
+
+        for x in 0..20 {
+            for y in -20..20 {
+                if x > 0 || y == 0 {
+                    round_trip("aim", Point::new(x, y));
+                }
+            }
+        }
+
+        assert!(aimed(Point::new(0, 5)).is_err());
+        assert!(aimed(Point::new(1, 1 << 40)).is_err());
+    }
+}
@@ -0,0 +1,404 @@
+//! A small language for writing submarine courses, lowered into the same
+//! directions as a plain list of commands:
+//!
+//! ```text
+//! # Dive in stages
+//! let depth = 4
+//! macro dive {
+//!     down depth
+//!     forward 2
+//! }
+//!
+//! repeat 3 { dive }
+//! up 12
+//! ```
+//!
+//! Statements are the three commands, `repeat N { ... }`, `macro name
+//! { ... }`, `let name = N`, and a macro's name on its own to run it. Any
+//! number can be a variable instead. Comments run from `#` to the end of
+//! the line.
+
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+use super::Direction;
+
+/// Most commands a script may expand to, so a few nested loops can't eat
+/// all the memory
+const MAX_COMMANDS: usize = 10_000_000;
+
+/// Most statements and loop iterations a script may run, so loops that
+/// don't produce commands still finish
+const MAX_STEPS: usize = 100_000_000;
+
+/// Where something is in the script, both counting from 1
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ScriptError {
+    pub at: Location,
+    pub message: String,
+}
+
+impl ScriptError {
+    fn new<S: Into<String>>(at: Location, message: S) -> Self {
+        ScriptError { at, message: message.into() }
+    }
+}
+
+impl Display for ScriptError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.at.line, self.at.column, self.message)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Number(u32),
+    Open,
+    Close,
+    Equals,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "`{}`", word),
+            Token::Number(n) => write!(f, "`{}`", n),
+            Token::Open => write!(f, "`{{`"),
+            Token::Close => write!(f, "`}}`"),
+            Token::Equals => write!(f, "`=`"),
+        }
+    }
+}
+
+/// Split a script into tokens, each with where it starts
+fn tokenize(source: &str) -> Result<Vec<(Token, Location)>, ScriptError> {
+    let mut tokens = Vec::new();
+
+    for (line, text) in source.lines().enumerate() {
+        let mut chars = text.chars().enumerate().peekable();
+
+        while let Some((column, c)) = chars.next() {
+            let at = Location { line: line + 1, column: column + 1 };
+
+            let token = match c {
+                '#' => break,
+                '{' => Token::Open,
+                '}' => Token::Close,
+                '=' => Token::Equals,
+                c if c.is_whitespace() => continue,
+                c if c.is_ascii_alphanumeric() || c == '_' => {
+                    let mut word = c.to_string();
+                    while let Some(&(_, c)) = chars.peek() {
+                        if !(c.is_ascii_alphanumeric() || c == '_') {
+                            break;
+                        }
+                        word.push(c);
+                        chars.next();
+                    }
+
+                    if word.starts_with(|c: char| c.is_ascii_digit()) {
+                        let n = word.parse()
+                            .map_err(|_| ScriptError::new(at, format!("invalid number `{}`", word)))?;
+                        Token::Number(n)
+                    } else {
+                        Token::Word(word)
+                    }
+                },
+                c => return Err(ScriptError::new(at, format!("unexpected character `{}`", c))),
+            };
+
+            tokens.push((token, at));
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// A number, given directly or by a variable's name
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Number(u32),
+    Variable(String),
+}
+
+#[derive(Debug)]
+enum Statement {
+    Command(fn(u32) -> Direction, Value),
+    Repeat(Value, Vec<(Statement, Location)>),
+    Macro(String, Vec<(Statement, Location)>),
+    Let(String, Value),
+    Call(String),
+}
+
+/// Words that can't be used to name a macro or variable
+const KEYWORDS: &[&str] = &["forward", "up", "down", "repeat", "macro", "let"];
+
+struct Parser {
+    tokens: std::iter::Peekable<std::vec::IntoIter<(Token, Location)>>,
+
+    /// Where the script ends, for errors about it ending too soon
+    end: Location,
+}
+
+impl Parser {
+    /// Parse statements until the end of a block, or of the script if
+    /// `block` is missing. `block` is where the block was opened.
+    fn statements(&mut self, block: Option<Location>) -> Result<Vec<(Statement, Location)>, ScriptError> {
+        let mut statements = Vec::new();
+
+        loop {
+            match (self.tokens.peek(), block) {
+                (Some((Token::Close, _)), Some(_)) => {
+                    self.tokens.next();
+                    return Ok(statements);
+                },
+                (None, Some(at)) => return Err(ScriptError::new(at, "block is never closed")),
+                (None, None) => return Ok(statements),
+                _ => statements.push(self.statement()?),
+            }
+        }
+    }
+
+    fn statement(&mut self) -> Result<(Statement, Location), ScriptError> {
+        let (token, at) = self.next()?;
+        let word = match token {
+            Token::Word(word) => word,
+            token => return Err(ScriptError::new(at, format!("expected a statement, found {}", token))),
+        };
+
+        let statement = match word.as_str() {
+            "forward" => Statement::Command(Direction::Forward, self.value()?),
+            "up" => Statement::Command(Direction::Up, self.value()?),
+            "down" => Statement::Command(Direction::Down, self.value()?),
+            "repeat" => {
+                let count = self.value()?;
+                Statement::Repeat(count, self.block()?)
+            },
+            "macro" => {
+                let name = self.name()?;
+                Statement::Macro(name, self.block()?)
+            },
+            "let" => {
+                let name = self.name()?;
+                self.expect(Token::Equals)?;
+                Statement::Let(name, self.value()?)
+            },
+            _ => Statement::Call(word),
+        };
+
+        Ok((statement, at))
+    }
+
+    fn block(&mut self) -> Result<Vec<(Statement, Location)>, ScriptError> {
+        let at = self.expect(Token::Open)?;
+        self.statements(Some(at))
+    }
+
+    fn value(&mut self) -> Result<Value, ScriptError> {
+        match self.next()? {
+            (Token::Number(n), _) => Ok(Value::Number(n)),
+            (Token::Word(word), at) if KEYWORDS.contains(&word.as_str()) =>
+                Err(ScriptError::new(at, format!("expected a number, found `{}`", word))),
+            (Token::Word(word), _) => Ok(Value::Variable(word)),
+            (token, at) => Err(ScriptError::new(at, format!("expected a number, found {}", token))),
+        }
+    }
+
+    fn name(&mut self) -> Result<String, ScriptError> {
+        match self.next()? {
+            (Token::Word(word), at) if KEYWORDS.contains(&word.as_str()) =>
+                Err(ScriptError::new(at, format!("`{}` can't be used as a name", word))),
+            (Token::Word(word), _) => Ok(word),
+            (token, at) => Err(ScriptError::new(at, format!("expected a name, found {}", token))),
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<Location, ScriptError> {
+        match self.next()? {
+            (token, at) if token == expected => Ok(at),
+            (token, at) => Err(ScriptError::new(at, format!("expected {}, found {}", expected, token))),
+        }
+    }
+
+    fn next(&mut self) -> Result<(Token, Location), ScriptError> {
+        self.tokens.next()
+            .ok_or_else(|| ScriptError::new(self.end, "unexpected end of script"))
+    }
+}
+
+/// A parsed script, ready to be expanded into directions
+#[derive(Debug)]
+pub struct Script {
+    statements: Vec<(Statement, Location)>,
+}
+
+impl Script {
+    pub fn parse(source: &str) -> Result<Script, ScriptError> {
+        let end = Location {
+            line: source.lines().count().max(1),
+            column: source.lines().last().map_or(0, |line| line.chars().count()) + 1,
+        };
+
+        let mut parser = Parser { tokens: tokenize(source)?.into_iter().peekable(), end };
+        Ok(Script { statements: parser.statements(None)? })
+    }
+
+    /// Run the script to flatten it into the directions it describes
+    pub fn expand(&self) -> Result<Vec<Direction>, ScriptError> {
+        self.expand_within(MAX_STEPS)
+    }
+
+    fn expand_within(&self, max_steps: usize) -> Result<Vec<Direction>, ScriptError> {
+        let mut expansion = Expansion {
+            variables: HashMap::new(),
+            macros: HashMap::new(),
+            calls: Vec::new(),
+            steps: 0,
+            max_steps,
+            directions: Vec::new(),
+        };
+
+        expansion.run(&self.statements)?;
+        Ok(expansion.directions)
+    }
+}
+
+/// The state of a script as it's being expanded. Variables and macros can
+/// be used anywhere after they're defined, including inside macros that
+/// run later.
+struct Expansion<'a> {
+    variables: HashMap<&'a str, u32>,
+    macros: HashMap<&'a str, &'a [(Statement, Location)]>,
+
+    /// Macros currently running, to catch ones that run themselves
+    calls: Vec<&'a str>,
+
+    /// Statements and loop iterations run so far, and how many may be
+    steps: usize,
+    max_steps: usize,
+    directions: Vec<Direction>,
+}
+
+impl<'a> Expansion<'a> {
+    fn run(&mut self, statements: &'a [(Statement, Location)]) -> Result<(), ScriptError> {
+        for (statement, at) in statements {
+            self.step(*at)?;
+
+            match statement {
+                Statement::Command(command, value) => {
+                    if self.directions.len() == MAX_COMMANDS {
+                        return Err(ScriptError::new(*at, format!("script expands to more than {} commands", MAX_COMMANDS)));
+                    }
+
+                    let n = self.value(value, *at)?;
+                    self.directions.push(command(n));
+                },
+                Statement::Repeat(count, body) => {
+                    for _ in 0..self.value(count, *at)? {
+                        self.step(*at)?;
+                        self.run(body)?;
+                    }
+                },
+                Statement::Macro(name, body) => {
+                    self.macros.insert(name, body);
+                },
+                Statement::Let(name, value) => {
+                    let n = self.value(value, *at)?;
+                    self.variables.insert(name, n);
+                },
+                Statement::Call(name) => {
+                    let body = *self.macros.get(name.as_str())
+                        .ok_or_else(|| ScriptError::new(*at, format!("undefined macro `{}`", name)))?;
+                    if self.calls.contains(&name.as_str()) {
+                        return Err(ScriptError::new(*at, format!("macro `{}` runs itself", name)));
+                    }
+
+                    self.calls.push(name);
+                    self.run(body)?;
+                    self.calls.pop();
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    fn step(&mut self, at: Location) -> Result<(), ScriptError> {
+        self.steps += 1;
+        match self.steps > self.max_steps {
+            true => Err(ScriptError::new(at, format!("script runs for more than {} steps", self.max_steps))),
+            false => Ok(()),
+        }
+    }
+
+    fn value(&self, value: &Value, at: Location) -> Result<u32, ScriptError> {
+        match value {
+            Value::Number(n) => Ok(*n),
+            Value::Variable(name) => self.variables.get(name.as_str()).copied()
+                .ok_or_else(|| ScriptError::new(at, format!("undefined variable `{}`", name))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(source: &str) -> Result<Vec<String>, String> {
+        Script::parse(source)
+            .and_then(|script| script.expand())
+            .map(|directions| directions.iter().map(|direction| direction.to_string()).collect())
+            .map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn test_expand() {
+        let source = "\
+            # Dive in stages\n\
+            let depth = 4\n\
+            macro dive {\n\
+            \x20   down depth # then move on\n\
+            \x20   forward 2\n\
+            }\n\
+            repeat 2 { dive }\n\
+            let depth = 1\n\
+            dive\n\
+            up 9";
+
+        assert_eq!(expand(source).unwrap(), vec![
+            "down 4", "forward 2", "down 4", "forward 2", "down 1", "forward 2", "up 9",
+        ]);
+        assert_eq!(expand("repeat 2 { repeat 0 { up 1 } forward 3 }").unwrap(), vec!["forward 3", "forward 3"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(expand("forward 5\ndown\n"), Err(String::from("line 2, column 5: unexpected end of script")));
+        assert_eq!(expand("forward 5\ndown up 3"), Err(String::from("line 2, column 6: expected a number, found `up`")));
+        assert_eq!(expand("repeat 2 {\n  up 1\n"), Err(String::from("line 1, column 10: block is never closed")));
+        assert_eq!(expand("let 3 = 4"), Err(String::from("line 1, column 5: expected a name, found `3`")));
+        assert_eq!(expand("  forward -1"), Err(String::from("line 1, column 11: unexpected character `-`")));
+        assert_eq!(expand("up 99999999999"), Err(String::from("line 1, column 4: invalid number `99999999999`")));
+        assert_eq!(expand("forward n"), Err(String::from("line 1, column 1: undefined variable `n`")));
+        assert_eq!(expand("\n  sink"), Err(String::from("line 2, column 3: undefined macro `sink`")));
+        assert_eq!(expand("macro a { b }\nmacro b { a }\na"), Err(String::from("line 2, column 11: macro `a` runs itself")));
+    }
+
+    #[test]
+    fn test_step_limit() {
+        let expand = |source| Script::parse(source).unwrap().expand_within(1000).map_err(|err| err.to_string());
+
+        assert_eq!(expand("repeat 4294967295 {\n  repeat 4294967295 { }\n}"),
+            Err(String::from("line 2, column 3: script runs for more than 1000 steps")));
+        assert_eq!(expand("repeat 4294967295 { let n = 1 }"),
+            Err(String::from("line 1, column 21: script runs for more than 1000 steps")));
+        assert_eq!(expand("repeat 300 { forward 1 }").map(|directions| directions.len()), Ok(300));
+    }
+}
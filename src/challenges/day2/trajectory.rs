@@ -0,0 +1,201 @@
+use std::fmt::Write;
+
+use serde_json::{json, Value};
+
+use crate::geometry::{Point, Rect, Segment};
+use crate::grid::Grid;
+
+use super::{Direction, NavigationModel};
+use super::constraints::{Constraints, StepError};
+
+/// Largest size of the ASCII plot, in characters. Longer courses are scaled
+/// down to fit.
+const PLOT_WIDTH: usize = 60;
+const PLOT_HEIGHT: usize = 20;
+
+/// Size of the SVG plot's drawing area, and the space left around it for
+/// labels
+const SVG_WIDTH: f64 = 600.0;
+const SVG_HEIGHT: f64 = 300.0;
+const SVG_MARGIN: f64 = 60.0;
+
+/// The submarine's state after one step of its course
+#[derive(Clone, Debug, PartialEq)]
+struct Record {
+    step: usize,
+
+    /// The instruction followed to get here, missing for the starting point
+    command: Option<Direction>,
+    location: Point,
+    aim: i64,
+}
+
+/// Every state the submarine passes through on its course, from where it
+/// starts
+pub struct Trajectory {
+    records: Vec<Record>,
+}
+
+/// The state of a model that can be charted
+fn state(model: &dyn NavigationModel) -> (Point, i64) {
+    let location = model.charted().expect("trajectories need a charted model").location();
+    (location, model.aimed().map_or(0, |model| model.aim()))
+}
+
+impl Trajectory {
+    /// Follow each direction with `model`, recording its state after each.
+    /// Models without an aim are recorded with an aim of 0.
+    pub fn record<I: IntoIterator<Item = Direction>>(
+        model: &mut dyn NavigationModel,
+        directions: I,
+        constraints: &Constraints,
+    ) -> Result<Self, StepError> {
+        let (location, aim) = state(model);
+        let mut records = vec![Record { step: 0, command: None, location, aim }];
+
+        for (i, direction) in directions.into_iter().enumerate() {
+            model.go(&direction, constraints)
+                .map_err(|error| StepError { step: i + 1, direction, error })?;
+            let (location, aim) = state(model);
+            records.push(Record { step: i + 1, command: Some(direction), location, aim });
+        }
+
+        Ok(Trajectory { records })
+    }
+
+    fn command(record: &Record) -> String {
+        record.command.map(|command| command.to_string()).unwrap_or_default()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("step,command,horizontal,depth,aim\n");
+        for record in &self.records {
+            writeln!(csv, "{},{},{},{},{}", record.step, Trajectory::command(record),
+                record.location.x, record.location.y, record.aim).unwrap();
+        }
+
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        let records = self.records.iter()
+            .map(|record| json!({
+                "step": record.step,
+                "command": record.command.map(|command| command.to_string()),
+                "horizontal": record.location.x,
+                "depth": record.location.y,
+                "aim": record.aim,
+            }))
+            .collect();
+
+        serde_json::to_string_pretty(&Value::Array(records)).unwrap() + "\n"
+    }
+
+    fn bounds(&self) -> Rect {
+        Rect::bounding(self.records.iter().map(|record| record.location)).unwrap()
+    }
+
+    /// Plot depth against horizontal position, with the surface at the top,
+    /// joining up each step of the course
+    pub fn to_ascii(&self) -> String {
+        let bounds = self.bounds();
+        let (width, height) = (bounds.width().min(PLOT_WIDTH as i64), bounds.height().min(PLOT_HEIGHT as i64));
+        let scale = |value: i64, min: i64, range: i64, cells: i64| match range {
+            1 => 0,
+            _ => ((value - min) as f64 * (cells - 1) as f64 / (range - 1) as f64).round() as i64,
+        };
+        let cell = |location: Point| Point::new(
+            scale(location.x, bounds.min.x, bounds.width(), width),
+            scale(location.y, bounds.min.y, bounds.height(), height));
+
+        let mut plot = Grid::new(width as usize, height as usize, ' ');
+        for pair in self.records.windows(2) {
+            for point in Segment::new(cell(pair[0].location), cell(pair[1].location)).points() {
+                plot[(point.x as usize, point.y as usize)] = '#';
+            }
+        }
+
+        let labels = [bounds.min.y.to_string(), bounds.max.y.to_string()];
+        let margin = labels.iter().map(|label| label.len()).max().unwrap();
+
+        let mut text = String::new();
+        for (y, row) in plot.rows().enumerate() {
+            let label = match y {
+                0 => &labels[0],
+                _ if y == plot.height() - 1 => &labels[1],
+                _ => "",
+            };
+            writeln!(text, "{:>margin$} |{}", label, row.iter().collect::<String>().trim_end(),
+                margin = margin).unwrap();
+        }
+
+        let (first, last) = (bounds.min.x.to_string(), bounds.max.x.to_string());
+        writeln!(text, "{:>margin$} +{}", "", "-".repeat(plot.width()), margin = margin).unwrap();
+        writeln!(text, "{:>margin$}  {}{:>pad$}", "", first, last, margin = margin,
+            pad = plot.width().saturating_sub(first.len()).max(last.len() + 1)).unwrap();
+
+        text
+    }
+
+    /// Plot depth against horizontal position as an SVG line, stretched to
+    /// fill the drawing area
+    pub fn to_svg(&self) -> String {
+        let bounds = self.bounds();
+        let span = |range: i64| (range - 1).max(1) as f64;
+        let x = |value: i64| SVG_MARGIN + (value - bounds.min.x) as f64 / span(bounds.width()) * SVG_WIDTH;
+        let y = |value: i64| SVG_MARGIN / 2.0 + (value - bounds.min.y) as f64 / span(bounds.height()) * SVG_HEIGHT;
+
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" width=\"{}\">\n",
+            SVG_WIDTH + SVG_MARGIN * 2.0, SVG_HEIGHT + SVG_MARGIN, SVG_WIDTH + SVG_MARGIN * 2.0);
+
+        let points = self.records.iter()
+            .map(|record| format!("{:.1},{:.1}", x(record.location.x), y(record.location.y)))
+            .collect::<Vec<_>>();
+        writeln!(svg, "<polyline fill=\"none\" stroke=\"#08306b\" stroke-width=\"2\" points=\"{}\"/>",
+            points.join(" ")).unwrap();
+
+        let labels = [
+            (SVG_MARGIN - 5.0, y(bounds.min.y) + 4.0, "end", bounds.min.y),
+            (SVG_MARGIN - 5.0, y(bounds.max.y) + 4.0, "end", bounds.max.y),
+            (x(bounds.min.x), SVG_HEIGHT + SVG_MARGIN - 5.0, "start", bounds.min.x),
+            (x(bounds.max.x), SVG_HEIGHT + SVG_MARGIN - 5.0, "end", bounds.max.x),
+        ];
+        for (x, y, anchor, value) in labels {
+            writeln!(svg, "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"12\" text-anchor=\"{}\">{}</text>",
+                x, y, anchor, value).unwrap();
+        }
+
+        svg += "</svg>\n";
+        svg
+    }
+}
+
+/// How a recorded trajectory is given as a part's output
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Export {
+    Csv,
+    Json,
+    Ascii,
+    Svg,
+}
+
+impl Export {
+    pub fn parse(value: &str) -> Result<Export, String> {
+        match value {
+            "csv" => Ok(Export::Csv),
+            "json" => Ok(Export::Json),
+            "ascii" => Ok(Export::Ascii),
+            "svg" => Ok(Export::Svg),
+            _ => Err(format!("invalid trajectory format: {}", value)),
+        }
+    }
+
+    pub fn export(&self, trajectory: &Trajectory) -> String {
+        match self {
+            Export::Csv => trajectory.to_csv(),
+            Export::Json => trajectory.to_json(),
+            Export::Ascii => trajectory.to_ascii(),
+            Export::Svg => trajectory.to_svg(),
+        }
+    }
+}
@@ -1,5 +1,5 @@
-use std::collections::HashMap;
-use crate::challenges::Challenge;
+use crate::challenges::{self, Challenge};
+use crate::counter::{Counter, Tie};
 
 /// Simple sorting data structure - 0's go on the left and 1's go on the right,
 /// with the digit used to partition the input being specified by `sort_index`
@@ -20,7 +20,7 @@ impl LeftRight {
         for item in input {
             // The digits of the input are numbered from left to right,
             // somewhat counterintuitively.
-            match item.chars().skip(sort_index).next() {
+            match item.chars().nth(sort_index) {
                 // Zeroes go in the left bucket
                 Some('0') => result.left.push(item),
 
@@ -28,7 +28,7 @@ impl LeftRight {
                 Some('1') => result.right.push(item),
 
                 // There's no such thing as 2
-                _ => panic!("invalid binary number: {}", item),
+                _ => challenges::invalid_input(format!("invalid binary number: {}", item)),
             }
         }
 
@@ -56,57 +56,31 @@ impl Day3 {
 
 impl Challenge for Day3 {
     fn part_1(&self, input: Vec<String>) -> String {
-        // Count the number of 1's and 0's in each column by adding 1 for a
-        // 1, and subtracting 1 for a zero
-        let balance = input.into_iter()
-
-            // Fold all the lines into a hashmap mapping the digit position to
-            // the 1's and 0's balance
-            .fold(HashMap::new(), |mut acc: HashMap<usize, i32>, line| {
-                line.chars()
-
-                    // Map each character of the line to a balancing value
-                    .map(|c| match c {
-                        '0' => -1,
-                        '1' => 1,
-                        _ => panic!("invalid binary"),
-                    })
-
-                    // Reverse the direction of the numbers so that they're
-                    // indexed from right-to-left, which makes it easier to
-                    // convert the binary string into an integer later
-                    .rev()
-
-                    // Collect the balance values in the accumulator
-                    .enumerate()
-                    .for_each(|(i, n)| match acc.get_mut(&i) {
-                        Some(count) => *count += n,
-                        None => { acc.insert(i, n); },
-                    });
-                acc
-            });
-
-        let gamma_rate = balance.iter()
-
-            // Map the + or - balance values into 1's and 0's respectively
-            .map(|(i, n)| match *n {
-                n if n > 0 => (i, 1),
-                _ => (i, 0),
-            })
-
-            // Fold the digits from the last step into an integer with bit math
-            .fold(0, |acc, (i, n)| acc | n << i);
-
-        let epsilon_rate = balance.iter()
-
-            // Map the + or - balance values into 0's and 1's respectively
-            .map(|(i, n)| match *n {
-                n if n < 0 => (i, 1),
-                _ => (i, 0),
-            })
-
-            // Fold the digits from the last step into an integer with bit math
-            .fold(0, |acc, (i, n)| acc | n << i);
+        // Count the 1's and 0's in each column, reading the digits of the
+        // numbers from left to right
+        let width = input.first().map_or(0, |line| line.len());
+        let columns = (0..width)
+            .map(|i| input.iter()
+                .map(|line| match line.as_bytes().get(i) {
+                    Some(b'0') => '0',
+                    Some(b'1') => '1',
+                    _ => challenges::invalid_input(format!("invalid binary: {}", line)),
+                })
+                .collect::<Counter<char>>())
+            .collect::<Vec<_>>();
+
+        // Turn the winning digit of each column into an integer with bit
+        // math
+        let rate = |digit: fn(&Counter<char>) -> char| columns.iter()
+            .map(|column| (digit(column) == '1') as i64)
+            .fold(0, |acc, n| acc << 1 | n);
+
+        // Gamma takes the most common digit in each column, and epsilon the
+        // least common, settling ties the way part 2 does: 1 for the most
+        // common and 0 for the least. A column that's all one digit has no
+        // count at all for the other, so it has to be asked for by name.
+        let gamma_rate = rate(|column| *column.most_common(Tie::Largest).unwrap().0);
+        let epsilon_rate = rate(|column| *column.least_common_of(&['0', '1'], Tie::Smallest).unwrap().0);
 
         (gamma_rate * epsilon_rate).to_string()
     }
@@ -165,35 +139,38 @@ impl Challenge for Day3 {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::challenges::input_from_str;
+    use crate::challenges::{self, input_from_str};
 
     // Example data from the challenge description
-    const DATA: &'static str = r"
-        00100
-        11110
-        10110
-        10111
-        10101
-        01111
-        00111
-        11100
-        10000
-        11001
-        00010
-        01010
-    ";
+    fn data() -> Vec<String> {
+        input_from_str(&challenges::example(3).input)
+    }
 
     #[test]
     fn test_part_1() {
-        let input = input_from_str(DATA);
         let challenge = Day3::new();
-        assert_eq!(challenge.part_1(input), "198");
+        assert_eq!(Some(challenge.part_1(data())), challenges::example(3).answers[0]);
     }
 
     #[test]
     fn test_part_2() {
-        let input = input_from_str(DATA);
         let challenge = Day3::new();
-        assert_eq!(challenge.part_2(input), "230");
+        assert_eq!(Some(challenge.part_2(data())), challenges::example(3).answers[1]);
+    }
+
+    #[test]
+    fn test_uniform_column() {
+        // The first column is all 1's, so epsilon has a 0 there
+        let input = input_from_str("11\n10\n11");
+        let challenge = Day3::new();
+        assert_eq!(challenge.part_1(input), "0");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_tied_column() {
+        // The first column is tied, so gamma has a 1 there and epsilon a 0
+        let input = input_from_str("10\n00\n11\n00");
+        let challenge = Day3::new();
+        assert_eq!(challenge.part_1(input), "2");
+    }
+}
@@ -1,15 +1,17 @@
 use std::collections::HashSet;
 
-use crate::challenges::Challenge;
+use crate::cancel::CancellationToken;
+use crate::challenges::{self, Challenge};
+use crate::grid::Grid;
 
 /// Bingo board that keeps track of all the matching numbers called
 struct Board {
     calls: HashSet<i32>,
-    data: Vec<Vec<i32>>,
+    data: Grid<i32>,
 }
 
 impl Board {
-    fn new(data: Vec<Vec<i32>>) -> Self {
+    fn new(data: Grid<i32>) -> Self {
         Board {
             calls: HashSet::new(),
             data,
@@ -20,41 +22,39 @@ impl Board {
     ///
     /// Returns whether bingo has been achieved
     fn call(&mut self, number: i32) -> bool {
-        for (i, row) in self.data.iter().enumerate() {
-            for (j, col) in row.iter().enumerate() {
-                if *col == number {
-                    // Dab the number on the card
-                    self.calls.insert(number);
-
-                    // Check for bingo for this row and column
-                    return self.check(i, j);
-                }
-            }
+        match self.data.position(|num| *num == number) {
+            Some((x, y)) => {
+                // Dab the number on the card
+                self.calls.insert(number);
+
+                // Check for bingo for this row and column
+                self.check(x, y)
+            },
+            None => false,
         }
-
-        false
     }
 
     /// Returns whether bingo has been achieved on a particular row/column
-    fn check(&self, i: usize, j: usize) -> bool {
+    fn check(&self, x: usize, y: usize) -> bool {
         // You can't get bingo with fewer than 5 dabs
         if self.calls.len() < 5 {
             return false;
         }
 
-        // Count the number of dabs on the specified row - 5 dabs is bingo
-        let row_matches = self.data.get(i).unwrap().iter()
+        // Count the number of dabs on the specified row - a full row is bingo
+        let row_matches = self.data.row(y).iter()
             .filter(|num| self.calls.contains(num))
             .count();
-        if row_matches == 5 {
+        if row_matches == self.data.width() {
             return true;
         }
 
-        // Count the number of dabs on the specified column - 5 wins
-        let col_matches = self.data.iter()
-            .filter(|row| self.calls.contains(row.get(j).unwrap()))
+        // Count the number of dabs on the specified column - a full column
+        // wins too
+        let col_matches = self.data.column(x)
+            .filter(|num| self.calls.contains(num))
             .count();
-        if col_matches == 5 {
+        if col_matches == self.data.height() {
             return true;
         }
 
@@ -64,18 +64,20 @@ impl Board {
     /// Sum all the un-dabbed numbers on the board
     fn sum(&self) -> i32 {
         self.data.iter()
-            .map(|row| row.iter()
-                .filter(|num| !self.calls.contains(*num))
-                .sum::<i32>())
+            .filter(|num| !self.calls.contains(*num))
             .sum::<i32>()
     }
 }
 
-pub struct Day4 {}
+pub struct Day4 {
+    cancel: CancellationToken,
+}
 
 impl Day4 {
     pub fn new() -> Self {
-        Day4 {}
+        Day4 {
+            cancel: CancellationToken::new(),
+        }
     }
 
     /// Parse the input data to create a callout sequence and several
@@ -83,7 +85,7 @@ impl Day4 {
     /// numbers, then a series of space-separated matrices representing bingo
     /// cards. Each item is separated by a blank line:
     ///
-    /// ```
+    /// ```text
     /// 1,23,44,12,17,0,9,...
     ///
     ///  1 23  0 15 21
@@ -102,7 +104,8 @@ impl Day4 {
             .split(",")
 
             // Parse each number
-            .map(|num| num.parse::<i32>().unwrap())
+            .map(|num| num.parse::<i32>()
+                .unwrap_or_else(|_| challenges::invalid_input(format!("invalid call: {}", num))))
             .collect::<Vec<_>>();
 
         // All the remaining data is bingo cards
@@ -120,18 +123,10 @@ impl Day4 {
             .collect::<Vec<_>>()
             .chunks(5)
 
-            // Construct a board from each chunk
-            .map(|chunk| Board::new(chunk.into_iter()
-
-                // Bingo board numbers are whitespace separated
-                .map(|line| line.split_whitespace()
-                    .map(|num| num.parse::<i32>().unwrap())
-
-                    // Collect cells into a row of numbers
-                    .collect::<Vec<i32>>())
-
-                // Collect rows into a board
-                .collect::<Vec<_>>()))
+            // Construct a board from each chunk. Bingo board numbers are
+            // whitespace separated
+            .map(|chunk| Board::new(Grid::parse_words(chunk)
+                .unwrap_or_else(|err| challenges::invalid_input(format!("invalid board: {}", err)))))
 
             // Collect boards into a vector
             .collect::<Vec<_>>();
@@ -147,6 +142,8 @@ impl Challenge for Day4 {
         // Repeat the check for any winning boards for each call number
         // until a winner is found
         for call in calls {
+            self.cancel.check();
+
             let results = boards.iter_mut()
 
                 // Filter out all non-winning boards
@@ -160,8 +157,8 @@ impl Challenge for Day4 {
                 .collect::<Vec<_>>();
 
             // End the game as soon as a winner is found
-            if results.len() > 0 {
-                return (results.get(0).unwrap().sum() * call).to_string();
+            if !results.is_empty() {
+                return (results.first().unwrap().sum() * call).to_string();
             }
         };
 
@@ -176,6 +173,8 @@ impl Challenge for Day4 {
         let mut completed = Vec::new();
 
         for call in calls {
+            self.cancel.check();
+
             // Replace the set of boards on each iteration, since they will
             // continue to spuriously win if we keep calling numbers on them
             boards = boards.into_iter()
@@ -199,47 +198,31 @@ impl Challenge for Day4 {
         let (call, last) = completed.last().unwrap();
         (last.sum() * call).to_string()
     }
+
+    fn set_cancellation(&mut self, token: CancellationToken) {
+        self.cancel = token;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::challenges::input_from_str;
+    use crate::challenges::{self, input_from_str};
 
     // Example data from the challenge description
-    const DATA: &'static str = r"
-        7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
-
-        22 13 17 11  0
-         8  2 23  4 24
-        21  9 14 16  7
-         6 10  3 18  5
-         1 12 20 15 19
-
-         3 15  0  2 22
-         9 18 13 17  5
-        19  8  7 25 23
-        20 11 10 24  4
-        14 21 16 12  6
-
-        14 21 17 24  4
-        10 16 15  9 19
-        18  8 23 26 20
-        22 11 13  6  5
-         2  0 12  3  7
-    ";
+    fn data() -> Vec<String> {
+        input_from_str(&challenges::example(4).input)
+    }
 
     #[test]
     fn test_part_1() {
-        let input = input_from_str(DATA);
         let challenge = Day4::new();
-        assert_eq!(challenge.part_1(input), "4512");
+        assert_eq!(Some(challenge.part_1(data())), challenges::example(4).answers[0]);
     }
 
     #[test]
     fn test_part_2() {
-        let input = input_from_str(DATA);
         let challenge = Day4::new();
-        assert_eq!(challenge.part_2(input), "1924");
+        assert_eq!(Some(challenge.part_2(data())), challenges::example(4).answers[1]);
     }
 }
\ No newline at end of file
@@ -1,195 +1,321 @@
+use std::collections::HashSet;
 use std::fmt::{self, Display, Formatter};
 
-use itertools::Itertools;
+use crate::challenges::{self, Challenge, Render, Visual};
+use crate::geometry::{Point, Rect, Segment, Vector};
+use crate::grid::Grid;
 
-use crate::challenges::Challenge;
-
-struct Point {
-    x: usize,
-    y: usize,
-}
-
-impl From<&str> for Point {
-    fn from(data: &str) -> Self {
-        match data.split(",")
-            .map(|num| num.parse::<usize>().unwrap())
-            .collect_tuple()
-        {
-            Some((x, y)) => Point { x, y },
-            _ => panic!("invalid point: {}", data),
-        }
-    }
-}
-
-struct Line {
-    p1: Point,
-    p2: Point,
-}
-
-impl From<&str> for Line {
-    fn from(data: &str) -> Self {
-        match data.split_whitespace().collect_tuple() {
-            Some((p1, "->", p2)) => Line {
-                p1: Point::from(p1),
-                p2: Point::from(p2),
-            },
-            _ => panic!("invalid line: {}", data),
-        }
-    }
+/// Parse a line of the puzzle input, `x1,y1 -> x2,y2`
+fn parse_line(text: &str) -> Segment {
+    text.parse().unwrap_or_else(|err| challenges::invalid_input(err))
 }
 
+/// The ocean floor, counting how many lines cross each point
 struct Bitmap {
-    width: usize,
-    data: Vec<u8>,
+    grid: Grid<u8>,
 }
 
 impl Bitmap {
     fn new(width: usize, height: usize) -> Self {
         Bitmap {
-            width,
-            data: vec![0; width * height],
+            grid: Grid::new(width, height, 0),
         }
     }
 
-    fn write_line(&mut self, line: Line) {
-        if line.p1.x == line.p2.x {
-            self.write_v(line.p1.x, line.p1.y, line.p2.y);
-        } else if line.p1.y == line.p2.y {
-            self.write_h(line.p1.y, line.p1.x, line.p2.x);
-        } else {
-            self.write_diag(line);
+    /// Mark every point along a horizontal, vertical or diagonal line
+    fn write_line(&mut self, line: Segment) {
+        for point in line.points() {
+            self.grid[(point.x as usize, point.y as usize)] += 1;
         }
     }
 
-    fn write_h(&mut self, y: usize, x1: usize, x2: usize) {
-        let (x_left, x_right) = if x1 < x2 {
-            (x1, x2)
-        } else {
-            (x2, x1)
-        };
-
-        let start = self.width * y + x_left;
-        for i in 0..x_right - x_left + 1 {
-            let idx = start + i;
-            let point = self.data.get_mut(idx).unwrap();
-            *point += 1;
-        }
+    /// Count the points where at least two lines overlap
+    fn overlaps(&self) -> usize {
+        self.grid.iter()
+            .filter(|x| **x > 1)
+            .count()
     }
 
-    fn write_v(&mut self, x: usize, y1: usize, y2: usize) {
-        let (y_top, y_bottom) = if y1 < y2 {
-            (y1, y2)
-        } else {
-            (y2, y1)
-        };
+    /// Copy out the smallest region of the bitmap containing every point
+    /// that has been drawn on
+    fn crop(&self) -> Bitmap {
+        let drawn = self.grid.cells()
+            .filter(|(_, x)| **x > 0)
+            .map(|((x, y), _)| Point::new(x as i64, y as i64));
 
-        let start = self.width * y_top + x;
-        for i in 0..y_bottom - y_top + 1 {
-            let idx = start + (self.width * i);
-            let point = self.data.get_mut(idx).unwrap();
-            *point += 1;
+        match Rect::bounding(drawn) {
+            Some(rect) => Bitmap {
+                grid: self.grid.view(rect.min.x as usize, rect.min.y as usize,
+                    rect.width() as usize, rect.height() as usize).to_grid(),
+            },
+            None => Bitmap::new(0, 0),
         }
     }
 
-    fn write_diag(&mut self, l: Line) {
-        let (left, right) = if l.p1.x < l.p2.x {
-            (l.p1, l.p2)
-        } else {
-            (l.p2, l.p1)
-        };
-
-        let direction = if right.y > left.y { 1 } else { -1 };
-        let start = (self.width * left.y + left.x) as i32;
-        let step = |i: i32| i * self.width as i32 * direction;
-        for i in 0..(right.x - left.x + 1) as i32 {
-            let idx = start + step(i) + i;
-            let point = self.data.get_mut(idx as usize).unwrap();
-            *point += 1;
-        }
+    fn rows(&self) -> Vec<Vec<u32>> {
+        self.grid.rows()
+            .map(|row| row.iter()
+                .map(|x| *x as u32)
+                .collect())
+            .collect()
     }
 }
 
 impl Display for Bitmap {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        self.data.iter()
-            .enumerate()
-            .for_each(|(i, x)| {
-                if i % self.width == 0 {
-                    write!(f, "\n").unwrap();
-                }
-
-                match x {
-                    0 => write!(f, ".").unwrap(),
-                    _ => write!(f, "{}", x).unwrap(),
-                }
-            });
+        self.grid.display(|x| match x {
+            0 => String::from("."),
+            _ => x.to_string(),
+        }).fmt(f)
+    }
+}
 
-        Ok(())
+/// Parse the `size` parameter, the width and height of the ocean floor
+fn parse_size(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(size) if size > 0 => Ok(size),
+        _ => Err(format!("invalid grid size: {}", value)),
     }
 }
 
-pub struct Day5 {}
+pub struct Day5 {
+    /// Width and height of the bitmap the lines are drawn on
+    size: usize,
+}
 
 impl Day5 {
-    pub fn new() -> Day5 { Day5 {} }
+    pub fn new() -> Day5 { Day5 { size: 1000 } }
 }
 
 impl Challenge for Day5 {
     fn part_1(&self, input: Vec<String>) -> String {
-        let mut bitmap = Bitmap::new(1000, 1000);
+        let mut bitmap = Bitmap::new(self.size, self.size);
         input.into_iter()
-            .map(|line| Line::from(line.as_str()))
-            .filter(|line| line.p1.x == line.p2.x || line.p1.y == line.p2.y)
+            .map(|line| parse_line(&line))
+            .filter(|line| line.is_horizontal() || line.is_vertical())
             .for_each(|line| bitmap.write_line(line));
 
-        bitmap.data.into_iter()
-            .map(|x| if x > 1 { 1 } else { 0 })
-            .sum::<i32>()
-            .to_string()
+        bitmap.overlaps().to_string()
     }
 
     fn part_2(&self, input: Vec<String>) -> String {
-        let mut bitmap = Bitmap::new(1000, 1000);
+        let mut bitmap = Bitmap::new(self.size, self.size);
         input.into_iter()
-            .map(|line| Line::from(line.as_str()))
+            .map(|line| parse_line(&line))
             .for_each(|line| bitmap.write_line(line));
 
-        bitmap.data.into_iter()
-            .map(|x| if x > 1 { 1 } else { 0 })
-            .sum::<i32>()
-            .to_string()
+        bitmap.overlaps().to_string()
+    }
+
+    fn render(&self, input: Vec<String>) -> Option<Render> {
+        let mut bitmap = Bitmap::new(self.size, self.size);
+        input.into_iter()
+            .map(|line| parse_line(&line))
+            .for_each(|line| bitmap.write_line(line));
+
+        let bitmap = bitmap.crop();
+        Some(Render {
+            text: bitmap.to_string(),
+            visual: Visual::Grid(bitmap.rows()),
+        })
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name {
+            "size" => self.size = parse_size(value)?,
+            _ => return Err(format!("unknown parameter `{}`", name)),
+        }
+
+        Ok(())
+    }
+}
+
+/// A line expressed as a start point, a unit step in each axis, and the
+/// number of steps to the end point. Lines in the puzzle are only ever
+/// horizontal, vertical, or at 45 degrees, so every point along the line
+/// falls on an integer coordinate.
+struct Steps {
+    start: Point,
+    step: Vector,
+    len: i64,
+}
+
+impl Steps {
+    fn min_x(&self) -> i64 {
+        self.start.x.min(self.point(self.len).x)
+    }
+
+    fn max_x(&self) -> i64 {
+        self.start.x.max(self.point(self.len).x)
+    }
+
+    fn point(&self, t: i64) -> Point {
+        self.start + self.step * t
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        (0..=self.len).any(|t| self.point(t) == point)
+    }
+
+    /// Collect every integer point shared by this segment and `other`
+    fn intersect(&self, other: &Steps, points: &mut HashSet<Point>) {
+        // A line that starts and ends on the same point has no direction to
+        // solve for, so just check whether the other line passes through it
+        if self.len == 0 || other.len == 0 {
+            let (point, line) = if self.len == 0 { (self.start, other) } else { (other.start, self) };
+            if line.contains(point) {
+                points.insert(point);
+            }
+            return;
+        }
+
+        let (a, b) = (self.step, other.step);
+        let w = other.start - self.start;
+        let denominator = a.cross(b);
+
+        if denominator != 0 {
+            // The segments cross at exactly one point: solve
+            // `self.start + t*a = other.start + s*b` for `t` and `s`. Diagonals
+            // can cross between grid points, so both must be whole numbers.
+            let (t, s) = (w.cross(b), w.cross(a));
+            if t % denominator != 0 || s % denominator != 0 {
+                return;
+            }
+
+            let (t, s) = (t / denominator, s / denominator);
+            if (0..=self.len).contains(&t) && (0..=other.len).contains(&s) {
+                points.insert(self.point(t));
+            }
+        } else if w.cross(a) == 0 {
+            // The segments are collinear, so project the other segment onto
+            // this one and take the overlapping range of steps
+            let t0 = w.dot(a) / a.dot(a);
+            let t1 = t0 + other.len * b.dot(a) / a.dot(a);
+
+            let start = t0.min(t1).max(0);
+            let end = t0.max(t1).min(self.len);
+            points.extend((start..=end).map(|t| self.point(t)));
+        }
+    }
+}
+
+impl From<&Segment> for Steps {
+    fn from(line: &Segment) -> Self {
+        let v = line.end - line.start;
+        Steps {
+            start: line.start,
+            step: v.signum(),
+            len: v.chebyshev(),
+        }
+    }
+}
+
+/// Count overlapping points by intersecting the lines with each other
+/// rather than drawing them. Lines are swept from left to right so that
+/// only lines whose horizontal extents overlap are compared.
+pub struct Day5Sweep {}
+
+impl Day5Sweep {
+    pub fn new() -> Day5Sweep { Day5Sweep {} }
+
+    fn count_overlaps(lines: Vec<Segment>) -> usize {
+        let mut segments = lines.iter()
+            .map(Steps::from)
+            .collect::<Vec<_>>();
+        segments.sort_by_key(|segment| segment.min_x());
+
+        let mut points = HashSet::new();
+        for (i, segment) in segments.iter().enumerate() {
+            segments[i + 1..].iter()
+                .take_while(|other| other.min_x() <= segment.max_x())
+                .for_each(|other| segment.intersect(other, &mut points));
+        }
+
+        points.len()
+    }
+}
+
+impl Challenge for Day5Sweep {
+    fn part_1(&self, input: Vec<String>) -> String {
+        let lines = input.into_iter()
+            .map(|line| parse_line(&line))
+            .filter(|line| line.is_horizontal() || line.is_vertical())
+            .collect::<Vec<_>>();
+
+        Day5Sweep::count_overlaps(lines).to_string()
+    }
+
+    fn part_2(&self, input: Vec<String>) -> String {
+        let lines = input.into_iter()
+            .map(|line| parse_line(&line))
+            .collect::<Vec<_>>();
+
+        Day5Sweep::count_overlaps(lines).to_string()
+    }
+
+    /// Intersecting lines doesn't need a grid, so the size is only checked
+    /// for validity to keep parameters interchangeable with `Day5`
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name {
+            "size" => parse_size(value).map(|_| ()),
+            _ => Err(format!("unknown parameter `{}`", name)),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::challenges::input_from_str;
-
-    const DATA: &'static str = r"
-        0,9 -> 5,9
-        8,0 -> 0,8
-        9,4 -> 3,4
-        2,2 -> 2,1
-        7,0 -> 7,4
-        6,4 -> 2,0
-        0,9 -> 2,9
-        3,4 -> 1,4
-        0,0 -> 8,8
-        5,5 -> 8,2
-    ";
+    use crate::challenges::{self, input_from_str};
+
+    // Example data from the challenge description
+    fn data() -> Vec<String> {
+        input_from_str(&challenges::example(5).input)
+    }
 
     #[test]
     fn test_part_1() {
-        let input = input_from_str(DATA);
         let challenge = Day5::new();
-        assert_eq!(challenge.part_1(input), "5");
+        assert_eq!(Some(challenge.part_1(data())), challenges::example(5).answers[0]);
     }
 
     #[test]
     fn test_part_2() {
-        let input = input_from_str(DATA);
         let challenge = Day5::new();
-        assert_eq!(challenge.part_2(input), "12");
+        assert_eq!(Some(challenge.part_2(data())), challenges::example(5).answers[1]);
+    }
+
+    #[test]
+    fn test_sweep_part_1() {
+        let challenge = Day5Sweep::new();
+        assert_eq!(Some(challenge.part_1(data())), challenges::example(5).answers[0]);
+    }
+
+    #[test]
+    fn test_sweep_part_2() {
+        let challenge = Day5Sweep::new();
+        assert_eq!(Some(challenge.part_2(data())), challenges::example(5).answers[1]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_render() {
+        let render = Day5::new().render(data()).unwrap();
+        assert!(render.text.starts_with("1.1....11.\n.111...2..\n"));
+        match render.visual {
+            Visual::Grid(rows) => {
+                assert_eq!(rows.len(), 10);
+                assert_eq!(rows[0], vec![1, 0, 1, 0, 0, 0, 0, 1, 1, 0]);
+            },
+            _ => panic!("expected a grid"),
+        }
+    }
+
+    #[test]
+    fn test_size_param() {
+        let mut challenge = Day5::new();
+        challenge.set_param("size", "10").unwrap();
+        assert_eq!(challenge.part_2(data()), "12");
+        assert!(challenge.set_param("size", "-1").is_err());
+        assert!(Day5Sweep::new().set_param("size", "10").is_ok());
+    }
+}
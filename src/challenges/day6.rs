@@ -1,23 +1,17 @@
-use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
 
-use crate::challenges::Challenge;
+use crate::cancel::CancellationToken;
+use crate::challenges::{self, Challenge, Render, Visual};
+use crate::counter::Counter;
 
 pub struct Community {
-    ages: HashMap<u8, usize>,
+    ages: Counter<u8>,
+    cancel: CancellationToken,
 }
 
 impl Community {
-    fn new(individuals: Vec<u8>) -> Self {
-        let mut age_map = HashMap::new();
-        for age in individuals {
-            match age_map.get_mut(&age) {
-                Some(n) => *n += 1,
-                None => { age_map.insert(age, 1); },
-            };
-        }
-
-        Community { ages: age_map }
+    fn new(individuals: Vec<u8>, cancel: CancellationToken) -> Self {
+        Community { ages: individuals.into_iter().collect(), cancel }
     }
 }
 
@@ -27,95 +21,286 @@ impl Display for Community {
         for age in 0..max + 1 {
             write!(f, "\t{}", age).unwrap();
         }
-        write!(f, "\n").unwrap();
+        writeln!(f).unwrap();
 
         for age in 0..max + 1 {
             write!(f, "\t").unwrap();
             match self.ages.get(&age) {
-                Some(n) => write!(f, "{}", n).unwrap(),
-                None => write!(f, ".").unwrap(),
+                0 => write!(f, ".").unwrap(),
+                n => write!(f, "{}", n).unwrap(),
             }
         }
-        write!(f, "\n").unwrap();
+        writeln!(f).unwrap();
 
         Ok(())
     }
 }
 
+/// The population after each day, until it's too large to count
 impl Iterator for Community {
     type Item = usize;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut update = HashMap::new();
+        self.cancel.check();
+
+        let mut update = Counter::new();
         for (age, amount) in self.ages.iter() {
             match *age {
                 0 => {
-                    update.insert(6, update.get(&6)
-                        .map_or(*amount, |n| n + amount));
-                    update.insert(8, update.get(&8)
-                        .map_or(*amount, |n| n + amount));
-                },
-                a @ _ => {
-                    update.insert(a - 1, update.get(&(a-1))
-                        .map_or(*amount, |n| n + amount));
+                    update.checked_add(6, amount)?;
+                    update.checked_add(8, amount)?;
                 },
+                a => update.checked_add(a - 1, amount)?,
             };
         }
 
         self.ages = update;
-        Some(self.ages.values().sum())
+        self.ages.checked_total()
     }
 }
 
-pub struct Day6 {}
+/// Oldest a fish's timer can be: a newborn's
+const MAX_AGE: u8 = 8;
+
+/// The input is a comma-separated list of each fish's age
+fn parse_ages(input: Vec<String>) -> Vec<u8> {
+    input.into_iter()
+        .flat_map(|line| line.split(',')
+            .map(|num| match num.parse::<u8>() {
+                Ok(age) if age <= MAX_AGE => age,
+                _ => challenges::invalid_input(format!("invalid age: {} (ages run from 0 to {})", num, MAX_AGE)),
+            })
+            .collect::<Vec<u8>>())
+        .collect::<Vec<_>>()
+}
+
+/// The population after `days` days of a simulation
+fn population<I: Iterator<Item = usize>>(mut simulation: I, days: usize) -> String {
+    simulation.nth(days - 1)
+        .unwrap_or_else(|| panic!("the population is too large to count after {} days", days))
+        .to_string()
+}
+
+/// Parse the `days` parameter shared by both implementations
+fn parse_days(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(days) if days > 0 => Ok(days),
+        _ => Err(format!("invalid number of days: {}", value)),
+    }
+}
+
+pub struct Day6 {
+    /// Number of days to simulate, overriding the puzzle's 80 and 256
+    days: Option<usize>,
+    cancel: CancellationToken,
+}
 
 impl Day6 {
     pub fn new() -> Self {
-        Day6 {}
+        Day6 {
+            days: None,
+            cancel: CancellationToken::new(),
+        }
     }
 }
 
 impl Challenge for Day6 {
     fn part_1(&self, input: Vec<String>) -> String {
-        let community = Community::new(input.into_iter()
-            .flat_map(|line| line.split(",")
-                .map(|num| num.parse::<u8>().unwrap())
-                .collect::<Vec<u8>>())
-            .collect::<Vec<_>>());
+        let community = Community::new(parse_ages(input), self.cancel.clone());
+        population(community, self.days.unwrap_or(80))
+    }
+
+    fn part_2(&self, input: Vec<String>) -> String {
+        let community = Community::new(parse_ages(input), self.cancel.clone());
+        population(community, self.days.unwrap_or(256))
+    }
+
+    /// Show the starting population by age, and chart how the population
+    /// grows over the first part's simulation
+    fn render(&self, input: Vec<String>) -> Option<Render> {
+        let community = Community::new(parse_ages(input), self.cancel.clone());
+        let text = community.to_string();
+
+        let initial = community.ages.total();
+        let series = std::iter::once(initial)
+            .chain(community.take(self.days.unwrap_or(80)))
+            .collect();
+
+        Some(Render { text, visual: Visual::Series(series) })
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name {
+            "days" => self.days = Some(parse_days(value)?),
+            _ => return Err(format!("unknown parameter `{}`", name)),
+        }
+
+        Ok(())
+    }
+
+    fn set_cancellation(&mut self, token: CancellationToken) {
+        self.cancel = token;
+    }
+}
+
+/// The same population model as `Community`, but with the count of fish
+/// at each age held in a fixed array indexed by age
+struct School {
+    timers: [usize; MAX_AGE as usize + 1],
+    cancel: CancellationToken,
+}
+
+impl School {
+    fn new(individuals: Vec<u8>, cancel: CancellationToken) -> Self {
+        let mut timers = [0; MAX_AGE as usize + 1];
+        for age in individuals {
+            timers[age as usize] += 1;
+        }
+
+        School { timers, cancel }
+    }
+}
+
+/// The population after each day, until it's too large to count
+impl Iterator for School {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cancel.check();
+
+        // Every fish ages by a day, and fish at age 0 wrap around to 8 -
+        // those are the newborns. Their parents reset to age 6.
+        self.timers.rotate_left(1);
+        self.timers[6] = self.timers[6].checked_add(self.timers[8])?;
 
-        println!("{}", community);
-        community.skip(79).next().unwrap().to_string()
+        self.timers.iter().try_fold(0usize, |total, timer| total.checked_add(*timer))
+    }
+}
+
+pub struct Day6Array {
+    days: Option<usize>,
+    cancel: CancellationToken,
+}
+
+impl Day6Array {
+    pub fn new() -> Self {
+        Day6Array {
+            days: None,
+            cancel: CancellationToken::new(),
+        }
+    }
+}
+
+impl Challenge for Day6Array {
+    fn part_1(&self, input: Vec<String>) -> String {
+        let school = School::new(parse_ages(input), self.cancel.clone());
+        population(school, self.days.unwrap_or(80))
     }
 
     fn part_2(&self, input: Vec<String>) -> String {
-        let community = Community::new(input.into_iter()
-            .flat_map(|line| line.split(",")
-                .map(|num| num.parse::<u8>().unwrap())
-                .collect::<Vec<u8>>())
-            .collect::<Vec<_>>());
+        let school = School::new(parse_ages(input), self.cancel.clone());
+        population(school, self.days.unwrap_or(256))
+    }
 
-        community.skip(255).next().unwrap().to_string()
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name {
+            "days" => self.days = Some(parse_days(value)?),
+            _ => return Err(format!("unknown parameter `{}`", name)),
+        }
+
+        Ok(())
+    }
+
+    fn set_cancellation(&mut self, token: CancellationToken) {
+        self.cancel = token;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::challenges::input_from_str;
+    use crate::challenges::{self, input_from_str};
 
-    const DATA: &'static str = "3,4,3,1,2";
+    // Example data from the challenge description
+    fn data() -> Vec<String> {
+        input_from_str(&challenges::example(6).input)
+    }
 
     #[test]
     fn test_part_1() {
-        let input = input_from_str(DATA);
         let challenge = Day6::new();
-        assert_eq!(challenge.part_1(input), "5934");
+        assert_eq!(Some(challenge.part_1(data())), challenges::example(6).answers[0]);
     }
 
     #[test]
     fn test_part_2() {
-        let input = input_from_str(DATA);
         let challenge = Day6::new();
-        assert_eq!(challenge.part_2(input), "26984457539");
+        assert_eq!(Some(challenge.part_2(data())), challenges::example(6).answers[1]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_array_part_1() {
+        let challenge = Day6Array::new();
+        assert_eq!(Some(challenge.part_1(data())), challenges::example(6).answers[0]);
+    }
+
+    #[test]
+    fn test_array_part_2() {
+        let challenge = Day6Array::new();
+        assert_eq!(Some(challenge.part_2(data())), challenges::example(6).answers[1]);
+    }
+
+    #[test]
+    fn test_days_param() {
+        for mut challenge in [Box::new(Day6::new()) as Box<dyn Challenge>, Box::new(Day6Array::new())] {
+            challenge.set_param("days", "18").unwrap();
+            assert_eq!(challenge.part_1(data()), "26");
+            assert!(challenge.set_param("days", "0").is_err());
+            assert!(challenge.set_param("weeks", "1").is_err());
+        }
+    }
+
+    #[test]
+    fn test_overflow() {
+        for mut challenge in [Box::new(Day6::new()) as Box<dyn Challenge>, Box::new(Day6Array::new())] {
+            challenge.set_param("days", "1000").unwrap();
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| challenge.part_1(data())));
+            let message = result.unwrap_err().downcast::<String>().unwrap();
+            assert_eq!(*message, "the population is too large to count after 1000 days");
+        }
+    }
+
+    #[test]
+    fn test_invalid_age() {
+        for challenge in [Box::new(Day6::new()) as Box<dyn Challenge>, Box::new(Day6Array::new())] {
+            assert_eq!(challenges::rejection(|| challenge.part_1(input_from_str("3,9,1"))),
+                "invalid age: 9 (ages run from 0 to 8)");
+        }
+    }
+
+    #[test]
+    fn test_cancelled() {
+        let token = CancellationToken::new();
+        let mut community = Community::new(vec![3, 4, 3, 1, 2], token.clone());
+        assert_eq!(community.next(), Some(5));
+
+        token.cancel();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| community.next()));
+        assert!(result.unwrap_err().is::<crate::cancel::Cancelled>());
+    }
+
+    #[test]
+    fn test_render() {
+        let input = data();
+        let render = Day6::new().render(input).unwrap();
+        assert_eq!(render.text, "\t0\t1\t2\t3\t4\n\t.\t1\t1\t2\t1\n");
+        match render.visual {
+            Visual::Series(series) => {
+                assert_eq!(series.len(), 81);
+                assert_eq!(&series[..4], &[5, 5, 6, 7]);
+                assert_eq!(series[80], 5934);
+            },
+            _ => panic!("expected a series"),
+        }
+    }
+}
@@ -5,29 +5,241 @@ mod day4;
 mod day5;
 mod day6;
 
-pub trait Challenge {
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+
+use itertools::Itertools;
+
+use crate::cancel::CancellationToken;
+use crate::plugin::{self, PluginChallenge, PluginError};
+
+pub trait Challenge: Send {
     fn part_1(&self, input: Vec<String>) -> String;
     fn part_2(&self, input: Vec<String>) -> String;
+
+    /// Set a tuning parameter given on the command line as `name=value`.
+    /// Challenges without parameters reject everything.
+    fn set_param(&mut self, name: &str, _value: &str) -> Result<(), String> {
+        Err(format!("unknown parameter `{}`", name))
+    }
+
+    /// Hand the challenge a token to check in its long-running loops, so
+    /// that it can be stopped once it's no longer wanted
+    fn set_cancellation(&mut self, _token: CancellationToken) {}
+
+    /// Render the challenge's working state for the given input, for
+    /// challenges that have something worth looking at
+    fn render(&self, _input: Vec<String>) -> Option<Render> {
+        None
+    }
 }
 
-pub fn get_challenge(day: u8) -> Box<dyn Challenge> {
-    match day {
-        1 => Box::new(day1::Day1::new()),
-        2 => Box::new(day2::Day2::new()),
-        3 => Box::new(day3::Day3::new()),
-        4 => Box::new(day4::Day4::new()),
-        5 => Box::new(day5::Day5::new()),
-        6 => Box::new(day6::Day6::new()),
-        _ => unimplemented!(),
+/// Panic payload for input a challenge can't make sense of, as opposed to
+/// a bug in the challenge itself
+pub struct InvalidInput(pub String);
+
+/// Give up on input the challenge can't make sense of. Like cancellation,
+/// this unwinds without running the panic hook, as it's an expected way to
+/// stop rather than a bug.
+pub fn invalid_input<M: Display>(message: M) -> ! {
+    std::panic::resume_unwind(Box::new(InvalidInput(message.to_string())))
+}
+
+/// Picture of a challenge's working state
+pub struct Render {
+    /// The state as printed by its `Display` impl
+    pub text: String,
+    pub visual: Visual,
+}
+
+/// Structured form of a render, for output formats that can draw it
+pub enum Visual {
+    /// Counts on a grid, indexed by row and then column
+    Grid(Vec<Vec<u32>>),
+
+    /// A quantity measured at each step of a simulation
+    Series(Vec<usize>),
+}
+
+type Constructor = fn() -> Box<dyn Challenge>;
+
+/// Every built-in challenge implementation, by day and name. The first
+/// implementation listed for a day is the one used by default.
+const BUILTIN: &[(u8, &str, Constructor)] = &[
+    (1, "default", || Box::new(day1::Day1::new())),
+    (2, "default", || Box::new(day2::Day2::new())),
+    (3, "default", || Box::new(day3::Day3::new())),
+    (4, "default", || Box::new(day4::Day4::new())),
+    (5, "raster", || Box::new(day5::Day5::new())),
+    (5, "sweep", || Box::new(day5::Day5Sweep::new())),
+    (6, "hashmap", || Box::new(day6::Day6::new())),
+    (6, "array", || Box::new(day6::Day6Array::new())),
+];
+
+/// Where a challenge implementation comes from
+pub enum Source {
+    Builtin(Constructor),
+    Plugin(PluginChallenge),
+}
+
+/// A named implementation of a single day's challenge
+pub struct Implementation {
+    pub day: u8,
+    pub name: String,
+    pub source: Source,
+}
+
+impl Implementation {
+    pub fn new_challenge(&self) -> Box<dyn Challenge> {
+        match &self.source {
+            Source::Builtin(new) => new(),
+            Source::Plugin(plugin) => Box::new(plugin.clone()),
+        }
+    }
+}
+
+/// Failure to find a challenge in the registry
+#[derive(Debug)]
+pub enum LookupError {
+    NoSuchDay(u8),
+    NoSuchImpl { day: u8, name: String, available: Vec<String> },
+}
+
+impl Display for LookupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LookupError::NoSuchDay(day) => write!(f, "no challenge for day {}", day),
+            LookupError::NoSuchImpl { day, name, available } =>
+                write!(f, "no implementation `{}` for day {} (available: {})",
+                    name, day, available.join(", ")),
+        }
+    }
+}
+
+/// All the challenge implementations available to run: the built-in days
+/// plus anything loaded from plugins
+pub struct Registry {
+    implementations: Vec<Implementation>,
+
+    /// Plugins that failed to load, kept so they can be reported
+    pub errors: Vec<PluginError>,
+}
+
+impl Registry {
+    /// A registry containing only the challenges compiled into the crate
+    pub fn builtin() -> Self {
+        Registry {
+            implementations: BUILTIN.iter()
+                .map(|(day, name, new)| Implementation {
+                    day: *day,
+                    name: String::from(*name),
+                    source: Source::Builtin(*new),
+                })
+                .collect(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Add every challenge exported by the shared libraries in `dir`.
+    /// Libraries that fail to load are recorded in `errors` rather than
+    /// aborting the rest.
+    pub fn load_plugins(&mut self, dir: &Path) {
+        let (challenges, errors) = plugin::load_dir(dir);
+        self.implementations.extend(challenges.into_iter()
+            .map(|challenge| Implementation {
+                day: challenge.day,
+                name: challenge.name.clone(),
+                source: Source::Plugin(challenge),
+            }));
+        self.errors.extend(errors);
+    }
+
+    pub fn implementations(&self) -> &[Implementation] {
+        &self.implementations
+    }
+
+    /// Every implementation registered for `day`, default first
+    pub fn implementations_for(&self, day: u8) -> Vec<&Implementation> {
+        self.implementations.iter()
+            .filter(|implementation| implementation.day == day)
+            .collect()
+    }
+
+    /// List the days that have at least one implementation, in order
+    pub fn days(&self) -> Vec<u8> {
+        self.implementations.iter()
+            .map(|implementation| implementation.day)
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
+    /// Find a challenge for `day`. Without a `name` the first implementation
+    /// registered for the day is used, so built-in days take precedence over
+    /// plugins. Names may be given bare (`fast`) or qualified (`day7::fast`).
+    pub fn get_challenge(&self, day: u8, name: Option<&str>) -> Result<Box<dyn Challenge>, LookupError> {
+        let candidates = self.implementations_for(day);
+        let default = candidates.first()
+            .ok_or(LookupError::NoSuchDay(day))?;
+
+        let name = match name {
+            Some(name) => name,
+            None => return Ok(default.new_challenge()),
+        };
+
+        let prefix = format!("day{}::", day);
+        let name = name.strip_prefix(&prefix).unwrap_or(name);
+
+        candidates.iter()
+            .find(|implementation| implementation.name == name)
+            .map(|implementation| implementation.new_challenge())
+            .ok_or_else(|| LookupError::NoSuchImpl {
+                day,
+                name: name.to_string(),
+                available: candidates.iter()
+                    .map(|implementation| implementation.name.clone())
+                    .collect(),
+            })
+    }
+}
+
+/// Run a single part of a challenge, returning `None` if there is no such part
+pub fn run_part(challenge: &dyn Challenge, part: u8, input: Vec<String>) -> Option<String> {
+    match part {
+        1 => Some(challenge.part_1(input)),
+        2 => Some(challenge.part_2(input)),
+        _ => None,
     }
 }
 
 #[cfg(test)]
-fn input_from_str(input: &str) -> Vec<String> {
+pub(crate) fn input_from_str(input: &str) -> Vec<String> {
     input.lines()
         .filter_map(|line| match line.trim() {
-            s if s.len() > 0 => Some(String::from(s)),
+            s if !s.is_empty() => Some(String::from(s)),
             _ => None,
         })
         .collect::<Vec<_>>()
-}
\ No newline at end of file
+}
+
+/// The message a challenge rejects its input with, failing the test if it
+/// accepts the input or panics for some other reason
+#[cfg(test)]
+pub(crate) fn rejection<F: FnOnce() -> String>(solve: F) -> String {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(solve)) {
+        Ok(answer) => panic!("input was accepted, giving {}", answer),
+        Err(payload) => match payload.downcast::<InvalidInput>() {
+            Ok(rejected) => rejected.0,
+            Err(_) => panic!("challenge panicked instead of rejecting its input"),
+        },
+    }
+}
+
+/// The example from a day's puzzle description, as saved under
+/// `tests/fixtures` by the `examples` command
+#[cfg(test)]
+pub(crate) fn example(day: u8) -> crate::examples::Fixture {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    crate::examples::read(&dir, day)
+        .unwrap_or_else(|err| panic!("cannot read the day {} example: {}", day, err))
+}
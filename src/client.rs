@@ -0,0 +1,280 @@
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::input;
+
+/// Sent with every request so the site can tell who is calling
+const USER_AGENT: &str = concat!("aoc21/", env!("CARGO_PKG_VERSION"), " (github.com/carsonmyers/advent2021)");
+
+/// File in the cache directory holding the time of the last request
+const LAST_REQUEST_FILE: &str = "last_request";
+
+/// Anything that can go wrong talking to the site
+#[derive(Debug)]
+pub enum ClientError {
+    /// No session token in the environment or the config file
+    NoSession,
+
+    /// The site asked us to slow down
+    RateLimited,
+
+    /// The site answered with an error status
+    Status(u16, String),
+
+    /// The request never got an answer
+    Transport(String),
+
+    /// The site answered, but not in a way we understand
+    UnexpectedResponse(String),
+
+    Io(PathBuf, io::Error),
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::NoSession =>
+                write!(f, "no session token: set AOC_SESSION or `session` in the config file"),
+            ClientError::RateLimited =>
+                write!(f, "rate limited by the server, try again later"),
+            ClientError::Status(status, body) =>
+                write!(f, "server responded with {}: {}", status, body.lines().next().unwrap_or("")),
+            ClientError::Transport(err) =>
+                write!(f, "request failed: {}", err),
+            ClientError::UnexpectedResponse(text) =>
+                write!(f, "unexpected response: {}", text),
+            ClientError::Io(path, err) =>
+                write!(f, "cannot write {}: {}", path.display(), err),
+        }
+    }
+}
+
+/// An authenticated connection to the puzzle site, never sending requests
+/// closer together than the configured interval - even across runs
+pub struct Client {
+    base_url: String,
+    session: String,
+    interval: Duration,
+    cache_dir: PathBuf,
+}
+
+impl Client {
+    pub fn new(config: &Config) -> Result<Self, ClientError> {
+        Ok(Client {
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            session: config.session().ok_or(ClientError::NoSession)?,
+            interval: Duration::from_secs_f64(config.request_interval),
+            cache_dir: PathBuf::from(&config.cache_dir),
+        })
+    }
+
+    /// Download the puzzle input for a day
+    pub fn input(&self, year: u16, day: u8) -> Result<String, ClientError> {
+        self.wait_turn()?;
+
+        let response = ureq::get(&format!("{}/{}/day/{}/input", self.base_url, year, day))
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .call();
+
+        match response {
+            Ok(response) => response.into_string()
+                .map_err(|err| ClientError::Transport(err.to_string())),
+            Err(ureq::Error::Status(429, _)) => Err(ClientError::RateLimited),
+            Err(ureq::Error::Status(status, response)) =>
+                Err(ClientError::Status(status, response.into_string().unwrap_or_default())),
+            Err(err) => Err(ClientError::Transport(err.to_string())),
+        }
+    }
+
+    /// Post an answer for one part of a day, and read the verdict from the
+    /// page the site responds with
+    pub fn submit(&self, year: u16, day: u8, part: u8, answer: &str) -> Result<Verdict, ClientError> {
+        self.wait_turn()?;
+
+        let response = ureq::post(&format!("{}/{}/day/{}/answer", self.base_url, year, day))
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .send_form(&[("level", &part.to_string()), ("answer", answer)]);
+
+        let page = match response {
+            Ok(response) => response.into_string()
+                .map_err(|err| ClientError::Transport(err.to_string()))?,
+            Err(ureq::Error::Status(429, _)) => return Err(ClientError::RateLimited),
+            Err(ureq::Error::Status(status, response)) =>
+                return Err(ClientError::Status(status, response.into_string().unwrap_or_default())),
+            Err(err) => return Err(ClientError::Transport(err.to_string())),
+        };
+
+        Verdict::parse(&page)
+            .ok_or_else(|| ClientError::UnexpectedResponse(article_text(&page)))
+    }
+
+    /// Sleep until the interval since the last request has passed, then
+    /// record this request as the last one
+    fn wait_turn(&self) -> Result<(), ClientError> {
+        let stamp = self.cache_dir.join(LAST_REQUEST_FILE);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+
+        let last = fs::read_to_string(&stamp).ok()
+            .and_then(|millis| millis.trim().parse::<u64>().ok())
+            .map(Duration::from_millis);
+        if let Some(wait) = last.and_then(|last| (last + self.interval).checked_sub(now)) {
+            thread::sleep(wait);
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        fs::create_dir_all(&self.cache_dir)
+            .and_then(|_| fs::write(&stamp, now.as_millis().to_string()))
+            .map_err(|err| ClientError::Io(stamp, err))
+    }
+}
+
+/// What the site made of a submitted answer
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+
+    /// Wrong, without a hint which way
+    Wrong,
+
+    /// Answered too soon after the last attempt, with how long is left to
+    /// wait if the site said
+    RateLimited(Option<String>),
+
+    /// The part has already been solved, so the answer wasn't checked
+    AlreadySolved,
+}
+
+impl Verdict {
+    /// Read the verdict out of the page returned for a submission
+    pub fn parse(page: &str) -> Option<Verdict> {
+        let text = article_text(page);
+
+        if text.contains("That's the right answer") {
+            Some(Verdict::Correct)
+        } else if text.contains("That's not the right answer") {
+            Some(if text.contains("your answer is too high") {
+                Verdict::TooHigh
+            } else if text.contains("your answer is too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Wrong
+            })
+        } else if text.contains("You gave an answer too recently") {
+            // "You have 4m 32s left to wait."
+            let wait = text.split_once("You have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .map(|(wait, _)| wait.to_string());
+            Some(Verdict::RateLimited(wait))
+        } else if text.contains("You don't seem to be solving the right level") {
+            Some(Verdict::AlreadySolved)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the answer is known to be wrong, and shouldn't be sent again
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "too high"),
+            Verdict::TooLow => write!(f, "too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::RateLimited(Some(wait)) => write!(f, "rate limited ({} left to wait)", wait),
+            Verdict::RateLimited(None) => write!(f, "rate limited"),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+/// The text of the `<article>` in a page - where the site puts its message -
+/// with the tags stripped and whitespace collapsed
+fn article_text(page: &str) -> String {
+    // Starting just after `<article`, we're inside its opening tag
+    let (article, mut in_tag) = page.split_once("<article")
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or((page, false), |(article, _)| (article, true));
+
+    let mut text = String::new();
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {},
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Where an input ended up after `fetch`
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+    Downloaded(PathBuf),
+
+    /// The input was already on disk, so nothing was downloaded
+    Existing(PathBuf),
+}
+
+/// Download the input for a day into the input directory, unless it's
+/// already there
+pub fn fetch(config: &Config, year: u16, day: u8) -> Result<Fetched, ClientError> {
+    let path = input::day_path(Path::new(&config.input_dir), day);
+    if path.exists() {
+        return Ok(Fetched::Existing(path));
+    }
+
+    let input = Client::new(config)?.input(year, day)?;
+
+    fs::create_dir_all(&config.input_dir)
+        .and_then(|_| fs::write(&path, input))
+        .map_err(|err| ClientError::Io(path.clone(), err))?;
+
+    Ok(Fetched::Downloaded(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(message: &str) -> String {
+        format!("<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>", message)
+    }
+
+    #[test]
+    fn test_parse_verdict() {
+        assert_eq!(Verdict::parse(&page("That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer.")),
+            Some(Verdict::Correct));
+        assert_eq!(Verdict::parse(&page("That's not the right answer; your answer is too high.  If you're stuck...")),
+            Some(Verdict::TooHigh));
+        assert_eq!(Verdict::parse(&page("That's not the right answer; your answer is too low.")),
+            Some(Verdict::TooLow));
+        assert_eq!(Verdict::parse(&page("That's not the right answer.  If you're stuck...")),
+            Some(Verdict::Wrong));
+        assert_eq!(Verdict::parse(&page("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 4m 32s left to wait. <a href=\"/2021/day/1\">[Return to Day 1]</a>")),
+            Some(Verdict::RateLimited(Some(String::from("4m 32s")))));
+        assert_eq!(Verdict::parse(&page("You don't seem to be solving the right level.  Did you already complete it?")),
+            Some(Verdict::AlreadySolved));
+        assert_eq!(Verdict::parse(&page("Puzzle inputs differ by user.")), None);
+    }
+
+    #[test]
+    fn test_article_text() {
+        assert_eq!(article_text("<head><title>x</title></head><article class=\"day-desc\"><p>a <em>b</em>\n c</p></article>"), "a b c");
+    }
+}
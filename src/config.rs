@@ -0,0 +1,313 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use toml::Value;
+
+use crate::report::Format;
+
+/// Config file looked for in the working directory when none is given
+pub const DEFAULT_PATH: &str = "aoc21.toml";
+
+/// Anything that can go wrong reading a config file
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, toml::de::Error),
+
+    /// The file is valid TOML but a setting isn't understood
+    Invalid(PathBuf, String),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, err) =>
+                write!(f, "cannot read config {}: {}", path.display(), err),
+            ConfigError::Parse(path, err) =>
+                write!(f, "cannot parse config {}: {}", path.display(), err),
+            ConfigError::Invalid(path, reason) =>
+                write!(f, "invalid config {}: {}", path.display(), reason),
+        }
+    }
+}
+
+/// Project settings, read from `aoc21.toml` and overridden by command line
+/// flags. Every setting has a default so the file is optional.
+///
+/// ```toml
+/// input_dir = "input"
+/// format = "md"
+/// year = 2021
+/// timeout = 10.0
+/// cache_dir = ".aoc21"
+/// plugin_dir = "plugins"
+/// base_url = "https://adventofcode.com"
+/// request_interval = 5.0
+///
+/// [params.day6]
+/// days = 80
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    /// Directory puzzle inputs are read from
+    pub input_dir: String,
+
+    /// Report format used when none is given
+    pub format: Format,
+
+    /// Puzzle year
+    pub year: u16,
+
+    /// Seconds to let a challenge run before giving up
+    pub timeout: Option<f64>,
+
+    /// Directory for downloaded and recorded data
+    pub cache_dir: String,
+
+    /// Directory plugin libraries are loaded from
+    pub plugin_dir: String,
+
+    /// Challenge parameters for each day, as given to `--param`
+    pub params: BTreeMap<u8, BTreeMap<String, String>>,
+
+    /// Site puzzle inputs are downloaded from
+    pub base_url: String,
+
+    /// Session cookie for the site. The `AOC_SESSION` environment variable
+    /// takes precedence, which keeps the token out of the config file.
+    pub session: Option<String>,
+
+    /// Minimum number of seconds between requests to the site
+    pub request_interval: f64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            input_dir: String::from("input"),
+            format: Format::Markdown,
+            year: 2021,
+            timeout: None,
+            cache_dir: String::from(".aoc21"),
+            plugin_dir: String::from("plugins"),
+            params: BTreeMap::new(),
+            base_url: String::from("https://adventofcode.com"),
+            session: None,
+            request_interval: 5.0,
+        }
+    }
+}
+
+impl Config {
+    /// Load the config at `path`, or the default config file if no path is
+    /// given. Only an explicitly named file has to exist.
+    pub fn load(path: Option<&Path>) -> Result<Config, ConfigError> {
+        let (path, required) = match path {
+            Some(path) => (path, true),
+            None => (Path::new(DEFAULT_PATH), false),
+        };
+
+        match fs::read_to_string(path) {
+            Ok(text) => Config::parse(path, &text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound && !required => Ok(Config::default()),
+            Err(err) => Err(ConfigError::Io(path.to_path_buf(), err)),
+        }
+    }
+
+    /// Parse the text of a config file, starting from the defaults. `path`
+    /// is only used in errors.
+    pub fn parse(path: &Path, text: &str) -> Result<Config, ConfigError> {
+        let invalid = |reason: String| ConfigError::Invalid(path.to_path_buf(), reason);
+
+        let table = match text.parse::<Value>() {
+            Ok(Value::Table(table)) => table,
+            Ok(_) => return Err(invalid(String::from("expected a table"))),
+            Err(err) => return Err(ConfigError::Parse(path.to_path_buf(), err)),
+        };
+
+        let mut config = Config::default();
+        for (key, value) in table {
+            match (key.as_str(), value) {
+                ("input_dir", Value::String(dir)) => config.input_dir = dir,
+                ("format", Value::String(format)) => config.format = format.parse().map_err(invalid)?,
+                ("year", Value::Integer(year)) => config.year = u16::try_from(year)
+                    .map_err(|_| invalid(format!("invalid year: {}", year)))?,
+                ("timeout", Value::Float(timeout)) => config.timeout = Some(check_timeout(timeout).map_err(invalid)?),
+                ("timeout", Value::Integer(timeout)) => config.timeout = Some(check_timeout(timeout as f64).map_err(invalid)?),
+                ("cache_dir", Value::String(dir)) => config.cache_dir = dir,
+                ("plugin_dir", Value::String(dir)) => config.plugin_dir = dir,
+                ("params", Value::Table(days)) => config.params = Config::parse_params(days).map_err(invalid)?,
+                ("base_url", Value::String(url)) => config.base_url = url,
+                ("session", Value::String(session)) => config.session = Some(session),
+                ("request_interval", Value::Float(interval)) => config.request_interval = check_interval(interval).map_err(invalid)?,
+                ("request_interval", Value::Integer(interval)) => config.request_interval = check_interval(interval as f64).map_err(invalid)?,
+                (key, value) => return Err(invalid(format!("unexpected setting `{}` = {}", key, value))),
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Parse the `[params.dayN]` tables
+    fn parse_params(days: toml::value::Table) -> Result<BTreeMap<u8, BTreeMap<String, String>>, String> {
+        days.into_iter()
+            .map(|(key, params)| {
+                let day = key.strip_prefix("day")
+                    .and_then(|day| day.parse::<u8>().ok())
+                    .ok_or_else(|| format!("parameters must be grouped by day, as in [params.day6]: {}", key))?;
+
+                let params = match params {
+                    Value::Table(params) => params,
+                    _ => return Err(format!("parameters for {} must be a table", key)),
+                };
+
+                let params = params.into_iter()
+                    .map(|(name, value)| match value {
+                        Value::String(value) => (name, value),
+                        value => (name, value.to_string()),
+                    })
+                    .collect();
+
+                Ok((day, params))
+            })
+            .collect()
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout.map(Duration::from_secs_f64)
+    }
+
+    /// Parameters configured for a day, in the order they should be applied
+    pub fn params_for(&self, day: u8) -> Vec<(String, String)> {
+        self.params.get(&day)
+            .map(|params| params.iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect())
+            .unwrap_or_default()
+    }
+
+    /// The session token, from the environment or the config file
+    pub fn session(&self) -> Option<String> {
+        env::var("AOC_SESSION").ok()
+            .filter(|session| !session.is_empty())
+            .or_else(|| self.session.clone())
+    }
+
+    /// Render the config in the same format it's read from
+    pub fn to_toml(&self) -> String {
+        let mut table = toml::value::Table::new();
+        table.insert(String::from("input_dir"), Value::String(self.input_dir.clone()));
+        table.insert(String::from("format"), Value::String(String::from(match self.format {
+            Format::Markdown => "md",
+            Format::Html => "html",
+        })));
+        table.insert(String::from("year"), Value::Integer(self.year as i64));
+        if let Some(timeout) = self.timeout {
+            table.insert(String::from("timeout"), Value::Float(timeout));
+        }
+        table.insert(String::from("cache_dir"), Value::String(self.cache_dir.clone()));
+        table.insert(String::from("plugin_dir"), Value::String(self.plugin_dir.clone()));
+        table.insert(String::from("base_url"), Value::String(self.base_url.clone()));
+        if let Some(session) = &self.session {
+            table.insert(String::from("session"), Value::String(session.clone()));
+        }
+        table.insert(String::from("request_interval"), Value::Float(self.request_interval));
+
+        let params = self.params.iter()
+            .map(|(day, params)| {
+                let params = params.iter()
+                    .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+                    .collect();
+                (format!("day{}", day), Value::Table(params))
+            })
+            .collect::<toml::value::Table>();
+        if !params.is_empty() {
+            table.insert(String::from("params"), Value::Table(params));
+        }
+
+        toml::to_string(&Value::Table(table)).unwrap()
+    }
+}
+
+/// Check a number of seconds can be waited for: not negative, NaN, or too
+/// long to represent
+fn check_seconds(what: &str, seconds: f64) -> Result<f64, String> {
+    Duration::try_from_secs_f64(seconds)
+        .map(|_| seconds)
+        .map_err(|_| format!("invalid {}: {}", what, seconds))
+}
+
+/// Check a timeout in seconds, from the config or the command line
+pub fn check_timeout(seconds: f64) -> Result<f64, String> {
+    check_seconds("timeout", seconds)
+}
+
+/// Check the time to wait between requests to the puzzle site
+fn check_interval(seconds: f64) -> Result<f64, String> {
+    check_seconds("request interval", seconds)
+}
+
+/// Parse a timeout in seconds given on the command line
+pub fn parse_timeout(value: &str) -> Result<f64, String> {
+    value.parse::<f64>()
+        .map_err(|_| format!("invalid timeout: {}", value))
+        .and_then(check_timeout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &str = r#"
+        input_dir = "puzzles"
+        format = "html"
+        timeout = 5
+
+        [params.day6]
+        days = 18
+
+        [params.day5]
+        size = 10
+    "#;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(Path::new("aoc21.toml"), DATA).unwrap();
+        assert_eq!(config.input_dir, "puzzles");
+        assert_eq!(config.format, Format::Html);
+        assert_eq!(config.year, 2021);
+        assert_eq!(config.timeout(), Some(Duration::from_secs(5)));
+        assert_eq!(config.params_for(6), vec![(String::from("days"), String::from("18"))]);
+        assert_eq!(config.params_for(1), vec![]);
+
+        // Rendering and re-reading the config gives the same settings
+        assert_eq!(Config::parse(Path::new("aoc21.toml"), &config.to_toml()).unwrap(), config);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let parse = |text: &str| Config::parse(Path::new("aoc21.toml"), text).unwrap_err().to_string();
+        assert_eq!(parse("colour = true"), "invalid config aoc21.toml: unexpected setting `colour` = true");
+        assert_eq!(parse("format = \"pdf\""), "invalid config aoc21.toml: unknown report format: pdf");
+        assert!(parse("[params.six]\ndays = 1").contains("grouped by day"));
+        assert!(parse("year = ").starts_with("cannot parse config aoc21.toml"));
+        assert_eq!(parse("timeout = -1"), "invalid config aoc21.toml: invalid timeout: -1");
+        assert_eq!(parse("timeout = nan"), "invalid config aoc21.toml: invalid timeout: NaN");
+        assert_eq!(parse("request_interval = -2"), "invalid config aoc21.toml: invalid request interval: -2");
+        assert_eq!(parse("request_interval = inf"), "invalid config aoc21.toml: invalid request interval: inf");
+        assert_eq!(parse("request_interval = nan"), "invalid config aoc21.toml: invalid request interval: NaN");
+    }
+
+    #[test]
+    fn test_parse_timeout() {
+        assert_eq!(parse_timeout("2.5"), Ok(2.5));
+        assert_eq!(parse_timeout("-0.5"), Err(String::from("invalid timeout: -0.5")));
+        assert_eq!(parse_timeout("NaN"), Err(String::from("invalid timeout: NaN")));
+        assert_eq!(parse_timeout("soon"), Err(String::from("invalid timeout: soon")));
+    }
+}
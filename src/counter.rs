@@ -0,0 +1,221 @@
+use std::collections::btree_map::{self, BTreeMap};
+use std::iter::FromIterator;
+
+/// Which key wins when several are equally common
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tie {
+    Smallest,
+    Largest,
+}
+
+impl Tie {
+    /// Whether `key` wins a tie against `other`
+    fn prefers<K: Ord>(&self, key: &K, other: &K) -> bool {
+        match self {
+            Tie::Smallest => key < other,
+            Tie::Largest => key > other,
+        }
+    }
+}
+
+/// A multiset: how many times each key has been counted. Keys with a count
+/// of zero aren't stored, and iteration is in key order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Counter<K: Ord> {
+    counts: BTreeMap<K, usize>,
+}
+
+impl<K: Ord> Counter<K> {
+    pub fn new() -> Self {
+        Counter { counts: BTreeMap::new() }
+    }
+
+    /// Count `key` another `n` times
+    pub fn add(&mut self, key: K, n: usize) {
+        if n > 0 {
+            *self.counts.entry(key).or_insert(0) += n;
+        }
+    }
+
+    /// Count `key` another `n` times, or give `None` and leave the count
+    /// alone if it would overflow
+    pub fn checked_add(&mut self, key: K, n: usize) -> Option<()> {
+        let count = self.get(&key).checked_add(n)?;
+        if count > 0 {
+            self.counts.insert(key, count);
+        }
+        Some(())
+    }
+
+    /// Take up to `n` away from the count of `key`, returning how many were
+    /// actually removed
+    pub fn remove(&mut self, key: &K, n: usize) -> usize {
+        let count = match self.counts.get_mut(key) {
+            Some(count) => count,
+            None => return 0,
+        };
+
+        let removed = n.min(*count);
+        *count -= removed;
+        if *count == 0 {
+            self.counts.remove(key);
+        }
+        removed
+    }
+
+    pub fn get(&self, key: &K) -> usize {
+        self.counts.get(key).copied().unwrap_or(0)
+    }
+
+    /// Number of distinct keys
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Sum of all the counts
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Sum of all the counts, or `None` if it overflows
+    pub fn checked_total(&self) -> Option<usize> {
+        self.counts.values().try_fold(0usize, |total, count| total.checked_add(*count))
+    }
+
+    /// Each key with its count, in key order
+    pub fn iter(&self) -> impl Iterator<Item = (&K, usize)> {
+        self.counts.iter().map(|(key, count)| (key, *count))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.counts.keys()
+    }
+
+    /// The key counted most often, with `tie` deciding between keys counted
+    /// equally often
+    pub fn most_common(&self, tie: Tie) -> Option<(&K, usize)> {
+        Counter::pick(self.iter(), tie, |count, best| count > best)
+    }
+
+    /// The key counted least often, with `tie` deciding between keys
+    /// counted equally often. Only keys that have been counted are
+    /// considered: see `least_common_of` for keys that might not have been.
+    pub fn least_common(&self, tie: Tie) -> Option<(&K, usize)> {
+        Counter::pick(self.iter(), tie, |count, best| count < best)
+    }
+
+    /// The least often counted of `keys`, including any with a count of
+    /// zero, with `tie` deciding between keys counted equally often
+    pub fn least_common_of<'a, I: IntoIterator<Item = &'a K>>(&self, keys: I, tie: Tie) -> Option<(&'a K, usize)> {
+        Counter::pick(keys.into_iter().map(|key| (key, self.get(key))), tie, |count, best| count < best)
+    }
+
+    /// Find the key whose count beats every other, or equals it and wins
+    /// the tie
+    fn pick<'a, I, F>(counts: I, tie: Tie, beats: F) -> Option<(&'a K, usize)>
+    where
+        I: Iterator<Item = (&'a K, usize)>,
+        F: Fn(usize, usize) -> bool,
+    {
+        counts.fold(None, |best, (key, count)| match best {
+            Some((best_key, best_count)) => {
+                let wins = beats(count, best_count) || (count == best_count && tie.prefers(key, best_key));
+                if wins { Some((key, count)) } else { best }
+            },
+            None => Some((key, count)),
+        })
+    }
+
+    /// Add every count from `other` to this counter
+    pub fn merge(&mut self, other: Counter<K>) {
+        for (key, count) in other {
+            self.add(key, count);
+        }
+    }
+}
+
+impl<K: Ord> FromIterator<K> for Counter<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<K: Ord> Extend<K> for Counter<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for key in iter {
+            self.add(key, 1);
+        }
+    }
+}
+
+impl<K: Ord> IntoIterator for Counter<K> {
+    type Item = (K, usize);
+    type IntoIter = btree_map::IntoIter<K, usize>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts() {
+        let mut counter = "abracadabra".chars().collect::<Counter<_>>();
+        assert_eq!(counter.get(&'a'), 5);
+        assert_eq!(counter.get(&'z'), 0);
+        assert_eq!((counter.len(), counter.total()), (5, 11));
+        assert_eq!(counter.iter().collect::<Vec<_>>(), vec![(&'a', 5), (&'b', 2), (&'c', 1), (&'d', 1), (&'r', 2)]);
+
+        assert_eq!(counter.remove(&'b', 5), 2);
+        assert_eq!(counter.remove(&'z', 1), 0);
+        assert_eq!(counter.keys().collect::<String>(), "acdr");
+    }
+
+    #[test]
+    fn test_most_and_least_common() {
+        let counter = "abracadabra".chars().collect::<Counter<_>>();
+        assert_eq!(counter.most_common(Tie::Smallest), Some((&'a', 5)));
+        assert_eq!(counter.least_common(Tie::Smallest), Some((&'c', 1)));
+        assert_eq!(counter.least_common(Tie::Largest), Some((&'d', 1)));
+
+        let even = "0110".chars().collect::<Counter<_>>();
+        assert_eq!(even.most_common(Tie::Smallest), Some((&'0', 2)));
+        assert_eq!(even.most_common(Tie::Largest), Some((&'1', 2)));
+        assert_eq!(Counter::<char>::new().most_common(Tie::Smallest), None);
+
+        // Keys that were never counted only come up when they're asked for
+        let ones = "111".chars().collect::<Counter<_>>();
+        assert_eq!(ones.least_common(Tie::Smallest), Some((&'1', 3)));
+        assert_eq!(ones.least_common_of(&['0', '1'], Tie::Smallest), Some((&'0', 0)));
+        assert_eq!(even.least_common_of(&['1', '0'], Tie::Smallest), Some((&'0', 2)));
+        assert_eq!(even.least_common_of(&[], Tie::Smallest), None);
+    }
+
+    #[test]
+    fn test_checked() {
+        let mut counter = Counter::new();
+        counter.add('a', usize::MAX - 1);
+        assert_eq!(counter.checked_add('a', 1), Some(()));
+        assert_eq!(counter.checked_add('a', 1), None);
+        assert_eq!(counter.get(&'a'), usize::MAX);
+
+        counter.add('b', 1);
+        assert_eq!(counter.checked_total(), None);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut counter = vec![1, 2, 2].into_iter().collect::<Counter<_>>();
+        counter.merge(vec![2, 3].into_iter().collect());
+        assert_eq!(counter.into_iter().collect::<Vec<_>>(), vec![(1, 1), (2, 3), (3, 1)]);
+    }
+}
@@ -0,0 +1,217 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// What one part of a puzzle description says about its example
+#[derive(Debug, PartialEq)]
+pub struct Part {
+    /// Every `<pre><code>` block, as plain text
+    pub blocks: Vec<String>,
+
+    /// The expected answer to the example
+    pub answer: Option<String>,
+}
+
+/// An example input with the answer to each part, ready to be written out
+/// for the tests
+#[derive(Debug, PartialEq)]
+pub struct Fixture {
+    pub input: String,
+    pub answers: Vec<Option<String>>,
+}
+
+/// Pull the example blocks and answers out of a saved puzzle page, one
+/// `Part` for each `<article>` - part one, then part two once it's unlocked
+pub fn extract(html: &str) -> Vec<Part> {
+    between(html, "<article", "</article>").into_iter()
+        .map(|article| {
+            let blocks = between(article, "<pre><code>", "</code></pre>").into_iter()
+                .map(|block| unescape(&strip_tags(block)))
+                .collect();
+
+            // Answers are emphasized, as code or as a plain number. The
+            // example's answer is the last one outside the blocks.
+            let prose = outside(article, "<pre>", "</pre>");
+            let code = between(&prose, "<code><em>", "</em></code>");
+            let answer = match code.last() {
+                Some(answer) => Some(answer.to_string()),
+                None => between(&prose, "<em>", "</em>").into_iter()
+                    .rev()
+                    .find(|text| !text.is_empty() && text.chars().all(|c| c.is_ascii_digit() || c == '-'))
+                    .map(String::from),
+            };
+
+            Part { blocks, answer: answer.map(|answer| unescape(&strip_tags(&answer))) }
+        })
+        .collect()
+}
+
+/// The example from a puzzle page: the first block in the first part, with
+/// each part's answer. Later blocks in a description are usually worked
+/// examples rather than input.
+pub fn fixture(parts: &[Part]) -> Option<Fixture> {
+    let input = parts.first()?.blocks.first()?.clone();
+    Some(Fixture {
+        input,
+        answers: parts.iter().map(|part| part.answer.clone()).collect(),
+    })
+}
+
+/// Write a fixture as `dir/dayN/input`, plus `dir/dayN/partP` holding the
+/// answer to each part that has one. Returns the files written.
+pub fn write(dir: &Path, day: u8, fixture: &Fixture) -> io::Result<Vec<PathBuf>> {
+    let dir = dir.join(format!("day{}", day));
+    fs::create_dir_all(&dir)?;
+
+    let mut input = fixture.input.clone();
+    if !input.ends_with('\n') {
+        input.push('\n');
+    }
+
+    let mut files = vec![(dir.join("input"), input)];
+    for (part, answer) in fixture.answers.iter().enumerate() {
+        if let Some(answer) = answer {
+            files.push((dir.join(format!("part{}", part + 1)), format!("{}\n", answer)));
+        }
+    }
+
+    for (path, contents) in &files {
+        fs::write(path, contents)?;
+    }
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+/// Read a fixture written by `write` back
+pub fn read(dir: &Path, day: u8) -> io::Result<Fixture> {
+    let dir = dir.join(format!("day{}", day));
+    let input = fs::read_to_string(dir.join("input"))?;
+    let answers = (1..=2)
+        .map(|part| fs::read_to_string(dir.join(format!("part{}", part))).ok()
+            .map(|answer| answer.trim().to_string()))
+        .collect();
+
+    Ok(Fixture { input, answers })
+}
+
+/// Every piece of `text` found between `open` and `close`. The end of
+/// `open` is found by its last character, so `<article` matches the whole
+/// opening tag whatever its attributes.
+fn between<'a>(text: &'a str, open: &str, close: &str) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(open) {
+        rest = &rest[start + open.len()..];
+        if open.starts_with('<') && !open.ends_with('>') {
+            match rest.find('>') {
+                Some(end) => rest = &rest[end + 1..],
+                None => break,
+            }
+        }
+
+        match rest.find(close) {
+            Some(end) => {
+                found.push(&rest[..end]);
+                rest = &rest[end + close.len()..];
+            },
+            None => break,
+        }
+    }
+
+    found
+}
+
+/// `text` with everything between `open` and `close` removed
+fn outside(text: &str, open: &str, close: &str) -> String {
+    let mut kept = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(open) {
+        kept += &rest[..start];
+        rest = match rest[start..].find(close) {
+            Some(end) => &rest[start + end + close.len()..],
+            None => "",
+        };
+    }
+
+    kept + rest
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {},
+        }
+    }
+
+    text
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::challenges::{self, Registry};
+
+    const DAY1: &str = include_str!("../tests/fixtures/puzzles/day1.html");
+    const DAY6: &str = include_str!("../tests/fixtures/puzzles/day6.html");
+
+    #[test]
+    fn test_extract() {
+        let parts = extract(DAY1);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].blocks.len(), 2);
+        assert_eq!(parts[0].blocks[1].lines().nth(1), Some("200 (increased)"));
+        assert_eq!(parts[0].answer.as_deref(), Some("7"));
+        assert_eq!(parts[1].answer.as_deref(), Some("5"));
+
+        let parts = extract(DAY6);
+        assert_eq!(parts[0].blocks[0], "3,4,3,1,2");
+        assert_eq!(parts[0].answer.as_deref(), Some("5934"));
+        assert_eq!(parts[1].blocks, Vec::<String>::new());
+        assert_eq!(parts[1].answer.as_deref(), Some("26984457539"));
+    }
+
+    #[test]
+    fn test_fixtures_solve() {
+        // The extracted examples give the answers the puzzle text promises
+        for (day, html) in [(1, DAY1), (6, DAY6)] {
+            let fixture = fixture(&extract(html)).unwrap();
+            let challenge = Registry::builtin().get_challenge(day, None).unwrap();
+            for (part, answer) in fixture.answers.iter().enumerate() {
+                let input = challenges::input_from_str(&fixture.input);
+                assert_eq!(challenges::run_part(challenge.as_ref(), part as u8 + 1, input), answer.clone());
+            }
+        }
+    }
+
+    #[test]
+    fn test_write() {
+        let dir = std::env::temp_dir().join(format!("aoc21-examples-{}", std::process::id()));
+        let fixture = fixture(&extract(DAY6)).unwrap();
+
+        let files = write(&dir, 6, &fixture).unwrap();
+        assert_eq!(files, vec![dir.join("day6/input"), dir.join("day6/part1"), dir.join("day6/part2")]);
+        assert_eq!(fs::read_to_string(dir.join("day6/part2")).unwrap(), "26984457539\n");
+
+        let read = read(&dir, 6).unwrap();
+        assert_eq!(read.input, "3,4,3,1,2\n");
+        assert_eq!(read.answers, fixture.answers);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape(&strip_tags("a &lt;<em>b</em>&gt; &amp;lt;")), "a <b> &lt;");
+    }
+}
@@ -0,0 +1,186 @@
+use std::panic;
+use std::slice;
+
+use crate::abi;
+use crate::challenges::{self, InvalidInput, Registry};
+
+/// Status codes as they're named in the C header
+const STATUS_NAMES: &[(&str, i32)] = &[
+    ("AOC21_STATUS_OK", abi::STATUS_OK),
+    ("AOC21_STATUS_NO_SUCH_PART", abi::STATUS_NO_SUCH_PART),
+    ("AOC21_STATUS_INVALID_INPUT", abi::STATUS_INVALID_INPUT),
+    ("AOC21_STATUS_BUFFER_TOO_SMALL", abi::STATUS_BUFFER_TOO_SMALL),
+    ("AOC21_STATUS_NO_SUCH_DAY", abi::STATUS_NO_SUCH_DAY),
+    ("AOC21_STATUS_NULL_POINTER", abi::STATUS_NULL_POINTER),
+    ("AOC21_STATUS_PANIC", abi::STATUS_PANIC),
+];
+
+/// Solve one part of a built-in challenge on behalf of a foreign caller.
+///
+/// The input is `input_len` bytes of UTF-8 with lines separated by `\n`. On
+/// entry `out_len` holds the capacity of `out_buf`; on success the answer is
+/// written there (without a nul terminator) and `out_len` is set to its
+/// length. If the buffer is too small, `out_len` is set to the length
+/// required and nothing is written.
+///
+/// Returns one of the `STATUS_*` codes from `abi`. Input a challenge rejects
+/// is reported as `STATUS_INVALID_INPUT`, as is input that isn't UTF-8. Any
+/// other panic is caught and reported as `STATUS_PANIC`, without printing
+/// anything to the host's stderr.
+///
+/// # Safety
+///
+/// `input` must point to `input_len` readable bytes, `out_len` must point to
+/// a writable `usize`, and `out_buf` must point to at least `*out_len`
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn aoc21_solve(
+    day: u8,
+    part: u8,
+    input: *const u8,
+    input_len: usize,
+    out_buf: *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    if (input.is_null() && input_len > 0) || out_buf.is_null() || out_len.is_null() {
+        return abi::STATUS_NULL_POINTER;
+    }
+
+    let input = if input_len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(input, input_len)
+    };
+    let input = match std::str::from_utf8(input) {
+        Ok(input) => input,
+        Err(_) => return abi::STATUS_INVALID_INPUT,
+    };
+
+    // The host has its own idea of what goes to stderr, so keep the panic
+    // hook quiet while inside the challenge
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(|| solve(day, part, input));
+    panic::set_hook(hook);
+
+    let answer = match result {
+        Ok(Ok(answer)) => answer,
+        Ok(Err(status)) => return status,
+        Err(payload) if payload.is::<InvalidInput>() => return abi::STATUS_INVALID_INPUT,
+        Err(_) => return abi::STATUS_PANIC,
+    };
+
+    if answer.len() > *out_len {
+        *out_len = answer.len();
+        return abi::STATUS_BUFFER_TOO_SMALL;
+    }
+
+    std::ptr::copy_nonoverlapping(answer.as_ptr(), out_buf, answer.len());
+    *out_len = answer.len();
+    abi::STATUS_OK
+}
+
+fn solve(day: u8, part: u8, input: &str) -> Result<String, i32> {
+    let challenge = Registry::builtin()
+        .get_challenge(day, None)
+        .map_err(|_| abi::STATUS_NO_SUCH_DAY)?;
+
+    let input = input.lines()
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    challenges::run_part(challenge.as_ref(), part, input)
+        .ok_or(abi::STATUS_NO_SUCH_PART)
+}
+
+/// Render the C header for `aoc21_solve` and the plugin ABI
+pub fn header() -> String {
+    let mut header = String::from("\
+/* Generated by `aoc21 header` - do not edit by hand */
+#ifndef AOC21_H
+#define AOC21_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+");
+
+    header += &format!("#define AOC21_PLUGIN_ABI_VERSION {}\n\n", abi::PLUGIN_ABI_VERSION);
+
+    for (name, status) in STATUS_NAMES {
+        header += &format!("/* {} */\n#define {} {}\n", abi::describe_status(*status), name, status);
+    }
+
+    header += "
+/*
+ * Solve one part of a built-in challenge. On entry `out_len` holds the
+ * capacity of `out_buf`; on success it holds the length of the answer, which
+ * is not nul-terminated. Returns one of the AOC21_STATUS_* codes, with
+ * AOC21_STATUS_INVALID_INPUT for input the challenge can't make sense of
+ * and AOC21_STATUS_PANIC if it fails for any other reason.
+ */
+int32_t aoc21_solve(uint8_t day, uint8_t part,
+                    const uint8_t *input, size_t input_len,
+                    uint8_t *out_buf, size_t *out_len);
+
+/* Plugin entry point solving one part of a challenge, as for aoc21_solve */
+typedef int32_t (*aoc21_solve_fn)(uint8_t part,
+                                  const uint8_t *input, size_t input_len,
+                                  uint8_t *out_buf, size_t *out_len);
+
+/* One challenge implementation exported by a plugin */
+struct aoc21_challenge {
+    uint8_t day;
+    const char *name;
+    aoc21_solve_fn solve;
+};
+
+/*
+ * Plugins export these two functions: the ABI version they were built
+ * against, and a table of the challenges they implement.
+ */
+uint32_t aoc21_plugin_abi_version(void);
+const struct aoc21_challenge *aoc21_plugin_challenges(size_t *count);
+
+#endif /* AOC21_H */
+";
+
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(day: u8, part: u8, input: &str, capacity: usize) -> (i32, String, usize) {
+        let mut buf = vec![0u8; capacity];
+        let mut len = capacity;
+        let status = unsafe {
+            aoc21_solve(day, part, input.as_ptr(), input.len(), buf.as_mut_ptr(), &mut len)
+        };
+        buf.truncate(len.min(capacity));
+        (status, String::from_utf8(buf).unwrap(), len)
+    }
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(call(1, 1, "199\n200\n208", 16), (abi::STATUS_OK, String::from("2"), 1));
+        assert_eq!(call(99, 1, "", 16).0, abi::STATUS_NO_SUCH_DAY);
+        assert_eq!(call(1, 3, "", 16).0, abi::STATUS_NO_SUCH_PART);
+        assert_eq!(call(1, 1, "not a number", 16).0, abi::STATUS_INVALID_INPUT);
+        assert_eq!(unsafe { aoc21_solve(1, 1, [0xff].as_ptr(), 1, [0u8; 4].as_mut_ptr(), &mut 4) },
+            abi::STATUS_INVALID_INPUT);
+
+        // No board ever wins, which the challenge treats as a bug rather
+        // than bad input
+        let board = "1 2 3 4 5\n".repeat(5);
+        assert_eq!(call(4, 1, &format!("99\n\n{}", board), 16).0, abi::STATUS_PANIC);
+    }
+
+    #[test]
+    fn test_buffer_too_small() {
+        let (status, _, len) = call(6, 2, "3,4,3,1,2", 4);
+        assert_eq!(status, abi::STATUS_BUFFER_TOO_SMALL);
+        assert_eq!(len, 11);
+    }
+}
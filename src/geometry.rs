@@ -0,0 +1,335 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// A position on an integer grid. `y` grows downwards, as in puzzle
+/// diagrams and `Grid`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// A displacement between two points
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Vector {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub fn new(x: i64, y: i64) -> Self {
+        Point { x, y }
+    }
+
+    /// Distance moving only along the axes
+    pub fn manhattan(&self, other: Point) -> i64 {
+        (*self - other).manhattan()
+    }
+
+    /// Distance moving along the axes or diagonally, like a king in chess
+    pub fn chebyshev(&self, other: Point) -> i64 {
+        (*self - other).chebyshev()
+    }
+
+    /// Move by `v`, or `None` if either coordinate would overflow
+    pub fn checked_add(&self, v: Vector) -> Option<Point> {
+        Some(Point::new(self.x.checked_add(v.x)?, self.y.checked_add(v.y)?))
+    }
+}
+
+impl Vector {
+    pub fn new(x: i64, y: i64) -> Self {
+        Vector { x, y }
+    }
+
+    pub fn manhattan(&self) -> i64 {
+        self.x.abs() + self.y.abs()
+    }
+
+    pub fn chebyshev(&self) -> i64 {
+        self.x.abs().max(self.y.abs())
+    }
+
+    /// The vector with each component reduced to -1, 0 or 1
+    pub fn signum(&self) -> Vector {
+        Vector::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn dot(&self, other: Vector) -> i64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// The z component of the 3D cross product: zero when the vectors are
+    /// parallel
+    pub fn cross(&self, other: Vector) -> i64 {
+        self.x * other.y - self.y * other.x
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, v: Vector) -> Point {
+        Point::new(self.x + v.x, self.y + v.y)
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, v: Vector) {
+        *self = *self + v;
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Point;
+
+    fn sub(self, v: Vector) -> Point {
+        Point::new(self.x - v.x, self.y - v.y)
+    }
+}
+
+impl SubAssign<Vector> for Point {
+    fn sub_assign(&mut self, v: Vector) {
+        *self = *self - v;
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, other: Point) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, other: Vector) -> Vector {
+        Vector::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, other: Vector) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<i64> for Vector {
+    type Output = Vector;
+
+    fn mul(self, n: i64) -> Vector {
+        Vector::new(self.x * n, self.y * n)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector::new(-self.x, -self.y)
+    }
+}
+
+/// Points are written `x,y`, as in puzzle inputs
+impl FromStr for Point {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y) = s.split_once(',')
+            .ok_or_else(|| format!("invalid point: {}", s))?;
+
+        match (x.trim().parse(), y.trim().parse()) {
+            (Ok(x), Ok(y)) => Ok(Point::new(x, y)),
+            _ => Err(format!("invalid point: {}", s)),
+        }
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+/// A line between two points, both of which are on the line
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Segment {
+    pub start: Point,
+    pub end: Point,
+}
+
+impl Segment {
+    pub fn new(start: Point, end: Point) -> Self {
+        Segment { start, end }
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        self.start.y == self.end.y
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.start.x == self.end.x
+    }
+
+    /// Whether the segment runs at exactly 45 degrees
+    pub fn is_diagonal(&self) -> bool {
+        let v = self.end - self.start;
+        v.x != 0 && v.x.abs() == v.y.abs()
+    }
+
+    /// Every grid point along the segment from start to end. Horizontal,
+    /// vertical and diagonal segments pass exactly through each point; any
+    /// other angle gives the closest points, as a line is drawn on screen.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        // Bresenham's algorithm, stepping along whichever axis is longer
+        let v = self.end - self.start;
+        let step = v.signum();
+        let (dx, dy) = (v.x.abs(), -v.y.abs());
+
+        let mut point = self.start;
+        let mut error = dx + dy;
+        (0..=v.chebyshev()).map(move |_| {
+            let current = point;
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                point.x += step.x;
+            }
+            if doubled <= dx {
+                error += dx;
+                point.y += step.y;
+            }
+            current
+        })
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect::new(self.start, self.end)
+    }
+}
+
+/// Segments are written `x1,y1 -> x2,y2`
+impl FromStr for Segment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s.split_once("->")
+            .ok_or_else(|| format!("invalid line: {}", s))?;
+
+        Ok(Segment::new(start.parse()?, end.parse()?))
+    }
+}
+
+impl Display for Segment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.start, self.end)
+    }
+}
+
+/// An axis-aligned rectangle, including the points on its edges
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Rect {
+    /// The rectangle with `a` and `b` at opposite corners
+    pub fn new(a: Point, b: Point) -> Self {
+        Rect {
+            min: Point::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Point::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    /// The smallest rectangle containing every point, if there are any
+    pub fn bounding<I: IntoIterator<Item = Point>>(points: I) -> Option<Rect> {
+        points.into_iter()
+            .map(|point| Rect::new(point, point))
+            .reduce(|a, b| a.union(&b))
+    }
+
+    pub fn width(&self) -> i64 {
+        self.max.x - self.min.x + 1
+    }
+
+    pub fn height(&self) -> i64 {
+        self.max.y - self.min.y + 1
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    /// The smallest rectangle containing both
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect {
+            min: Point::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Point::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(segment: &str) -> Vec<String> {
+        segment.parse::<Segment>().unwrap()
+            .points()
+            .map(|point| point.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let (a, b) = (Point::new(1, 2), Point::new(4, -2));
+        assert_eq!(b - a, Vector::new(3, -4));
+        assert_eq!(a + (b - a) * 2, Point::new(7, -6));
+        assert_eq!(a - -Vector::new(1, 1), Point::new(2, 3));
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(Vector::new(2, 0).cross(Vector::new(4, 0)), 0);
+        assert_eq!(Vector::new(1, 1).dot(Vector::new(2, -1)), 1);
+        assert_eq!(a.checked_add(Vector::new(1, 1)), Some(Point::new(2, 3)));
+        assert_eq!(Point::new(i64::MAX, 0).checked_add(Vector::new(1, 0)), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("3,-4".parse(), Ok(Point::new(3, -4)));
+        assert_eq!(" 0,9 -> 5,9 ".parse(), Ok(Segment::new(Point::new(0, 9), Point::new(5, 9))));
+        assert_eq!("3".parse::<Point>(), Err(String::from("invalid point: 3")));
+        assert!("0,9 5,9".parse::<Segment>().is_err());
+        assert_eq!(Segment::new(Point::new(1, 1), Point::ORIGIN).to_string(), "1,1 -> 0,0");
+    }
+
+    #[test]
+    fn test_points() {
+        assert_eq!(points("2,2 -> 2,1"), vec!["2,2", "2,1"]);
+        assert_eq!(points("9,4 -> 7,4"), vec!["9,4", "8,4", "7,4"]);
+        assert_eq!(points("8,0 -> 6,2"), vec!["8,0", "7,1", "6,2"]);
+        assert_eq!(points("0,0 -> 4,2"), vec!["0,0", "1,1", "2,1", "3,2", "4,2"]);
+        assert_eq!(points("5,5 -> 5,5"), vec!["5,5"]);
+
+        let line = "8,0 -> 0,8".parse::<Segment>().unwrap();
+        assert!(line.is_diagonal() && !line.is_horizontal() && !line.is_vertical());
+    }
+
+    #[test]
+    fn test_rect() {
+        let rect = Rect::bounding(vec![Point::new(3, 1), Point::new(-1, 4), Point::new(0, 0)]).unwrap();
+        assert_eq!(rect, Rect::new(Point::new(3, 4), Point::new(-1, 0)));
+        assert_eq!((rect.width(), rect.height()), (5, 5));
+        assert!(rect.contains(Point::new(3, 4)));
+        assert!(!rect.contains(Point::new(4, 4)));
+        assert_eq!(Rect::bounding(Vec::new()), None);
+    }
+}
@@ -0,0 +1,408 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+/// Anything that can go wrong parsing a grid
+#[derive(Debug, PartialEq)]
+pub enum GridError {
+    /// A row has a different number of cells than the first
+    Ragged { row: usize, expected: usize, found: usize },
+
+    /// A cell couldn't be parsed
+    InvalidCell { x: usize, y: usize, text: String },
+}
+
+impl Display for GridError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::Ragged { row, expected, found } =>
+                write!(f, "row {} has {} cells, expected {}", row, found, expected),
+            GridError::InvalidCell { x, y, text } =>
+                write!(f, "invalid cell at ({}, {}): {}", x, y, text),
+        }
+    }
+}
+
+/// Direction a diagonal runs in, from its starting cell downwards
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Diagonal {
+    /// Down and to the right, like the main diagonal of a matrix
+    Main,
+
+    /// Down and to the left
+    Anti,
+}
+
+/// A rectangular grid of cells stored row by row, addressed by `(x, y)`
+/// with `(0, 0)` at the top left
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid with every cell set to `value`
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Grid { width, height, cells: vec![value; width * height] }
+    }
+
+    /// Swap rows and columns
+    pub fn transpose(&self) -> Grid<T> {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: (0..self.width)
+                .flat_map(|x| self.column(x).cloned())
+                .collect(),
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Build a grid from rows of cells, which must all be the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, GridError> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+
+        let mut cells = Vec::with_capacity(width * height);
+        for (y, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(GridError::Ragged { row: y, expected: width, found: row.len() });
+            }
+            cells.extend(row);
+        }
+
+        Ok(Grid { width, height, cells })
+    }
+
+    /// Parse a grid with one character per cell, like a map drawn in text
+    pub fn parse_chars<S, F>(lines: &[S], mut cell: F) -> Result<Self, GridError>
+    where
+        S: AsRef<str>,
+        F: FnMut(char) -> Option<T>,
+    {
+        let rows = lines.iter()
+            .enumerate()
+            .map(|(y, line)| line.as_ref().chars()
+                .enumerate()
+                .map(|(x, c)| cell(c)
+                    .ok_or_else(|| GridError::InvalidCell { x, y, text: c.to_string() }))
+                .collect::<Result<Vec<_>, _>>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Grid::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if self.in_bounds(x, y) {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if self.in_bounds(x, y) {
+            self.cells.get_mut(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Every cell, row by row
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// Every cell with its position, row by row
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells.iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+
+    /// Position of the first cell, row by row, matching `predicate`
+    pub fn position<F: FnMut(&T) -> bool>(&self, predicate: F) -> Option<(usize, usize)> {
+        self.cells.iter()
+            .position(predicate)
+            .map(|i| (i % self.width, i / self.width))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` would panic on a zero-width grid
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+            .skip(x)
+            .step_by(self.width.max(1))
+            .take(if x < self.width { self.height } else { 0 })
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// Cells along a diagonal starting from `(x, y)`, until it leaves the
+    /// grid
+    pub fn diagonal(&self, x: usize, y: usize, direction: Diagonal) -> impl Iterator<Item = &T> {
+        let step = match direction {
+            Diagonal::Main => 1,
+            Diagonal::Anti => -1,
+        };
+
+        (0..)
+            .map(move |i| ((x as isize + step * i as isize) as usize, y + i))
+            .map_while(move |(x, y)| self.get(x, y))
+    }
+
+    /// Positions of the up to four cells sharing an edge with `(x, y)`
+    pub fn neighbours_4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(x, y, &[(0, -1), (-1, 0), (1, 0), (0, 1)])
+    }
+
+    /// Positions of the up to eight cells sharing an edge or a corner with
+    /// `(x, y)`
+    pub fn neighbours_8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(x, y, &[(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)])
+    }
+
+    fn neighbours(&self, x: usize, y: usize, offsets: &'static [(isize, isize)]) -> impl Iterator<Item = (usize, usize)> + '_ {
+        offsets.iter()
+            .map(move |(dx, dy)| ((x as isize + dx) as usize, (y as isize + dy) as usize))
+            .filter(move |(x, y)| self.in_bounds(*x, *y))
+    }
+
+    /// A view of the `width` by `height` region with its top left corner at
+    /// `(x, y)`, clipped to the grid
+    pub fn view(&self, x: usize, y: usize, width: usize, height: usize) -> View<'_, T> {
+        let x = x.min(self.width);
+        let y = y.min(self.height);
+        View {
+            grid: self,
+            x,
+            y,
+            width: width.min(self.width - x),
+            height: height.min(self.height - y),
+        }
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Display the grid with each cell formatted by `cell`. Cells are joined
+    /// with no separator unless one is set.
+    pub fn display<F: Fn(&T) -> String>(&self, cell: F) -> GridDisplay<'_, T, F> {
+        GridDisplay { grid: self, cell, separator: "" }
+    }
+}
+
+impl<T: FromStr> Grid<T> {
+    /// Parse a grid of whitespace separated values, like a table of numbers
+    pub fn parse_words<S: AsRef<str>>(lines: &[S]) -> Result<Self, GridError> {
+        let rows = lines.iter()
+            .enumerate()
+            .map(|(y, line)| line.as_ref().split_whitespace()
+                .enumerate()
+                .map(|(x, word)| word.parse::<T>()
+                    .map_err(|_| GridError::InvalidCell { x, y, text: word.to_string() }))
+                .collect::<Result<Vec<_>, _>>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Grid::from_rows(rows)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y)
+            .unwrap_or_else(|| panic!("({}, {}) is outside the {}x{} grid", x, y, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("({}, {}) is outside the {}x{} grid", x, y, width, height))
+    }
+}
+
+/// Each row on its own line, with cells shown as they display themselves
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.display(|cell| cell.to_string()).fmt(f)
+    }
+}
+
+/// A rectangular region of a grid, borrowed rather than copied
+pub struct View<'a, T> {
+    grid: &'a Grid<T>,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, T> View<'a, T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get a cell by its position within the view
+    pub fn get(&self, x: usize, y: usize) -> Option<&'a T> {
+        if x < self.width && y < self.height {
+            self.grid.get(self.x + x, self.y + y)
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, y: usize) -> &'a [T] {
+        &self.grid.row(self.y + y)[self.x..self.x + self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> + '_ {
+        (0..self.height).map(move |y| self.row(y))
+    }
+}
+
+impl<'a, T: Clone> View<'a, T> {
+    /// Copy the region out into a grid of its own
+    pub fn to_grid(&self) -> Grid<T> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.rows().flat_map(|row| row.to_vec()).collect(),
+        }
+    }
+}
+
+/// Formats a grid with a custom cell format and separator, from
+/// `Grid::display`
+pub struct GridDisplay<'a, T, F> {
+    grid: &'a Grid<T>,
+    cell: F,
+    separator: &'a str,
+}
+
+impl<'a, T, F> GridDisplay<'a, T, F> {
+    /// Put `separator` between the cells of each row
+    pub fn separator(mut self, separator: &'a str) -> Self {
+        self.separator = separator;
+        self
+    }
+}
+
+impl<'a, T, F: Fn(&T) -> String> Display for GridDisplay<'a, T, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for row in self.grid.rows() {
+            let cells = row.iter().map(&self.cell).collect::<Vec<_>>();
+            writeln!(f, "{}", cells.join(self.separator))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &[&str] = &[
+        "123",
+        "456",
+    ];
+
+    fn grid() -> Grid<u32> {
+        Grid::parse_chars(DATA, |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 6);
+        assert_eq!(grid.get(3, 0), None);
+
+        let words = Grid::<i32>::parse_words(&[" 1 -2", "30  4"]).unwrap();
+        assert_eq!(words.row(1), &[30, 4]);
+
+        assert_eq!(Grid::<i32>::parse_words(&["1 2", "3"]),
+            Err(GridError::Ragged { row: 1, expected: 2, found: 1 }));
+        assert_eq!(Grid::parse_chars(&["1x"], |c| c.to_digit(10)).unwrap_err().to_string(),
+            "invalid cell at (1, 0): x");
+    }
+
+    #[test]
+    fn test_lines() {
+        let grid = grid();
+        assert_eq!(grid.rows().collect::<Vec<_>>(), vec![&[1, 2, 3], &[4, 5, 6]]);
+        assert_eq!(grid.column(1).collect::<Vec<_>>(), vec![&2, &5]);
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.diagonal(0, 0, Diagonal::Main).collect::<Vec<_>>(), vec![&1, &5]);
+        assert_eq!(grid.diagonal(2, 0, Diagonal::Anti).collect::<Vec<_>>(), vec![&3, &5]);
+        assert_eq!(grid.position(|n| *n == 5), Some((1, 1)));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = grid();
+        assert_eq!(grid.neighbours_4(0, 0).collect::<Vec<_>>(), vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours_8(1, 0).collect::<Vec<_>>(), vec![(0, 0), (2, 0), (0, 1), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn test_transpose_and_view() {
+        let grid = grid();
+        assert_eq!(grid.transpose().to_string(), "14\n25\n36\n");
+
+        let view = grid.view(1, 0, 5, 1);
+        assert_eq!((view.width(), view.height()), (2, 1));
+        assert_eq!(view.get(1, 0), Some(&3));
+        assert_eq!(view.get(0, 1), None);
+        assert_eq!(view.to_grid().to_string(), "23\n");
+    }
+
+    #[test]
+    fn test_display() {
+        let grid = grid();
+        assert_eq!(grid.to_string(), "123\n456\n");
+        assert_eq!(grid.display(|n| format!("{:>2}", n * 2)).separator(" ").to_string(), " 2  4  6\n 8 10 12\n");
+    }
+}
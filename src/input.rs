@@ -0,0 +1,76 @@
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
+
+/// Inputs built into the binary by the `embed-inputs` feature, by file name
+static EMBEDDED: &[(&str, &str)] = include!(concat!(env!("OUT_DIR"), "/embedded_inputs.rs"));
+
+/// Where a challenge's input comes from
+#[derive(Clone, Debug, PartialEq)]
+pub enum Input {
+    File(PathBuf),
+
+    /// A copy of `input/{name}` built into the binary
+    Embedded { name: &'static str, text: &'static str },
+}
+
+impl Input {
+    /// Read the input as a list of lines
+    pub fn read(&self) -> io::Result<Vec<String>> {
+        match self {
+            Input::File(path) => read(path),
+            Input::Embedded { text, .. } => Ok(text.lines().map(String::from).collect()),
+        }
+    }
+}
+
+impl Display for Input {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Input::File(path) => write!(f, "{}", path.display()),
+            Input::Embedded { name, .. } => write!(f, "{} (embedded)", name),
+        }
+    }
+}
+
+/// Find the input for a challenge. An explicitly given file wins, followed
+/// by a part-specific input and then the input for the whole day, both
+/// looked for in `input_dir` and then among the embedded inputs.
+pub fn resolve(input_dir: &str, day: u8, part: u8, file: Option<&str>) -> Option<Input> {
+    let input_dir = Path::new(input_dir);
+    let names = [format!("day{}_part{}", day, part), format!("day{}", day)];
+
+    let on_disk = file.map(PathBuf::from).into_iter()
+        .chain(names.iter().map(|name| input_dir.join(name)))
+        .find(|path| path.exists())
+        .map(Input::File);
+
+    on_disk.or_else(|| names.iter().find_map(|name| embedded_input(name)))
+}
+
+fn embedded_input(name: &str) -> Option<Input> {
+    EMBEDDED.iter()
+        .find(|(embedded, _)| *embedded == name)
+        .map(|(name, text)| Input::Embedded { name, text })
+}
+
+/// Names of the inputs built into the binary, like `day1` or `day5_part2`
+pub fn embedded() -> Vec<&'static str> {
+    EMBEDDED.iter().map(|(name, _)| *name).collect()
+}
+
+/// Where the input for a whole day lives, and where downloaded inputs are
+/// saved
+pub fn day_path(input_dir: &Path, day: u8) -> PathBuf {
+    input_dir.join(format!("day{}", day))
+}
+
+/// Read an input file as a list of lines
+pub fn read(path: &Path) -> io::Result<Vec<String>> {
+    File::open(path)
+        .map(|file| io::BufReader::new(file)
+            .lines()
+            .map_while(|line| line.ok())
+            .collect::<Vec<String>>())
+}
@@ -0,0 +1,269 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use serde_json::Value;
+
+use crate::report::DayReport;
+use crate::runner::Outcome;
+
+/// When each part of a day was solved, as unix timestamps
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Stars {
+    pub part_1: Option<u64>,
+    pub part_2: Option<u64>,
+}
+
+impl Stars {
+    /// Seconds between solving part 1 and part 2
+    pub fn delta(&self) -> Option<u64> {
+        self.part_1.zip(self.part_2)
+            .map(|(part_1, part_2)| part_2.saturating_sub(part_1))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Member {
+    pub id: u64,
+    pub name: String,
+    pub stars: u64,
+    pub local_score: u64,
+    pub last_star_ts: u64,
+    pub days: BTreeMap<u8, Stars>,
+}
+
+/// A private leaderboard as exported from the site
+#[derive(Debug, PartialEq)]
+pub struct Leaderboard {
+    pub year: u16,
+
+    /// Members in ranking order: by local score, then stars, then whoever
+    /// got their last star first
+    pub members: Vec<Member>,
+}
+
+/// Timestamps are numbers in current exports and strings in older ones
+fn timestamp(value: &Value) -> Option<u64> {
+    value.as_u64()
+        .or_else(|| value.as_str().and_then(|ts| ts.parse().ok()))
+}
+
+pub fn parse(text: &str) -> Result<Leaderboard, String> {
+    let value = serde_json::from_str::<Value>(text)
+        .map_err(|err| format!("invalid leaderboard: {}", err))?;
+
+    let year = value["event"].as_str()
+        .and_then(|event| event.parse().ok())
+        .ok_or("leaderboard has no event year")?;
+    let members = value["members"].as_object()
+        .ok_or("leaderboard has no members")?;
+
+    let mut members = members.values()
+        .map(|member| {
+            let id = member["id"].as_u64()
+                .ok_or_else(|| format!("member without an id: {}", member))?;
+
+            let days = member["completion_day_level"].as_object()
+                .map(|days| days.iter()
+                    .filter_map(|(day, parts)| Some((day.parse::<u8>().ok()?, Stars {
+                        part_1: timestamp(&parts["1"]["get_star_ts"]),
+                        part_2: timestamp(&parts["2"]["get_star_ts"]),
+                    })))
+                    .collect())
+                .unwrap_or_default();
+
+            Ok(Member {
+                id,
+                name: member["name"].as_str()
+                    .map_or_else(|| format!("(anonymous user #{})", id), String::from),
+                stars: member["stars"].as_u64().unwrap_or(0),
+                local_score: member["local_score"].as_u64().unwrap_or(0),
+                last_star_ts: timestamp(&member["last_star_ts"]).unwrap_or(0),
+                days,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    members.sort_by(|a, b| b.local_score.cmp(&a.local_score)
+        .then(b.stars.cmp(&a.stars))
+        .then(a.last_star_ts.cmp(&b.last_star_ts)));
+
+    Ok(Leaderboard { year, members })
+}
+
+/// When a day's puzzle unlocked: midnight US Eastern, 05:00 UTC
+pub fn unlock_time(year: u16, day: u8) -> u64 {
+    // Days since the epoch, counting years from March so leap days fall at
+    // the end. December 1st is day 275 of such a year, and 719468 is the
+    // count for 1970-01-01.
+    let y = year as u64;
+    let days = 365 * y + y / 4 - y / 100 + y / 400 + 275 + day as u64 - 1 - 719468;
+
+    days * 86400 + 5 * 3600
+}
+
+/// Format a number of seconds like `1h02m03s`, `4m05s` or `6s`
+pub fn format_seconds(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    match (hours, minutes) {
+        (0, 0) => format!("{}s", seconds),
+        (0, _) => format!("{}m{:02}s", minutes, seconds),
+        _ => format!("{}h{:02}m{:02}s", hours, minutes, seconds),
+    }
+}
+
+/// Every day anyone has a star for
+fn days(leaderboard: &Leaderboard) -> Vec<u8> {
+    let mut days = leaderboard.members.iter()
+        .flat_map(|member| member.days.keys().copied())
+        .collect::<Vec<_>>();
+    days.sort_unstable();
+    days.dedup();
+    days
+}
+
+fn name_width(leaderboard: &Leaderboard) -> usize {
+    leaderboard.members.iter()
+        .map(|member| member.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(4)
+}
+
+/// The ranking table, with each member's stars and local score
+pub fn ranking(leaderboard: &Leaderboard) -> String {
+    let width = name_width(leaderboard);
+    let mut table = format!("{:>4}  {:<width$}  {:>5}  {:>5}\n", "Rank", "Name", "Stars", "Score", width = width);
+
+    for (rank, member) in leaderboard.members.iter().enumerate() {
+        writeln!(table, "{:>4}  {:<width$}  {:>5}  {:>5}",
+            rank + 1, member.name, member.stars, member.local_score, width = width).unwrap();
+    }
+
+    table
+}
+
+/// How long each member took to get from part 1 to part 2 of each day
+pub fn deltas(leaderboard: &Leaderboard) -> String {
+    let width = name_width(leaderboard);
+    let days = days(leaderboard);
+
+    let mut table = format!("{:<width$}", "Name", width = width);
+    for day in &days {
+        write!(table, "  {:>9}", format!("Day {}", day)).unwrap();
+    }
+    table += "\n";
+
+    for member in &leaderboard.members {
+        write!(table, "{:<width$}", member.name, width = width).unwrap();
+        for day in &days {
+            let delta = member.days.get(day)
+                .and_then(Stars::delta)
+                .map_or_else(|| String::from("-"), format_seconds);
+            write!(table, "  {:>9}", delta).unwrap();
+        }
+        table += "\n";
+    }
+
+    table
+}
+
+/// Set the members' times against how long our own solutions take to run:
+/// the fastest time to each star after the puzzle unlocked, and the fastest
+/// part 1 to part 2 delta
+pub fn comparison(leaderboard: &Leaderboard, reports: &[DayReport]) -> String {
+    let mut table = format!("{:>3}  {:>9}  {:>9}  {:>9}  {:>12}  {:>12}\n",
+        "Day", "Part 1", "Part 2", "Delta", "Our part 1", "Our part 2");
+
+    for day in days(leaderboard) {
+        let unlock = unlock_time(leaderboard.year, day);
+        let stars = leaderboard.members.iter()
+            .filter_map(|member| member.days.get(&day))
+            .collect::<Vec<_>>();
+
+        let fastest = |times: Vec<Option<u64>>| times.into_iter()
+            .flatten()
+            .min()
+            .map_or_else(|| String::from("-"), format_seconds);
+        let part_1 = fastest(stars.iter().map(|s| s.part_1.map(|ts| ts.saturating_sub(unlock))).collect());
+        let part_2 = fastest(stars.iter().map(|s| s.part_2.map(|ts| ts.saturating_sub(unlock))).collect());
+        let delta = fastest(stars.iter().map(|s| s.delta()).collect());
+
+        // Our timings, where we have a solution and an input for the day
+        let ours = |part: u8| reports.iter()
+            .find(|report| report.day == day)
+            .and_then(|report| report.parts.iter().find(|p| p.part == part))
+            .map_or_else(|| String::from("-"), |p| match p.outcome {
+                Outcome::Solved(_) => format!("{:?}", p.elapsed),
+                Outcome::TimedOut => String::from("timed out"),
+                Outcome::Failed(_) => String::from("failed"),
+            });
+
+        writeln!(table, "{:>3}  {:>9}  {:>9}  {:>9}  {:>12}  {:>12}",
+            day, part_1, part_2, delta, ours(1), ours(2)).unwrap();
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    use crate::report::PartReport;
+
+    const DATA: &str = include_str!("../tests/fixtures/leaderboard.json");
+
+    #[test]
+    fn test_parse() {
+        let leaderboard = parse(DATA).unwrap();
+        assert_eq!(leaderboard.year, 2021);
+
+        let names = leaderboard.members.iter().map(|m| m.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["Dana", "Carson", "(anonymous user #103)"]);
+
+        let anonymous = &leaderboard.members[2];
+        assert_eq!(anonymous.last_star_ts, 1638500000);
+        assert_eq!(anonymous.days[&1], Stars { part_1: Some(1638500000), part_2: None });
+        assert_eq!(leaderboard.members[0].days[&2].delta(), Some(400));
+    }
+
+    #[test]
+    fn test_unlock_time() {
+        assert_eq!(unlock_time(2021, 1), 1638334800);
+        assert_eq!(unlock_time(2021, 25), 1640408400);
+        assert_eq!(unlock_time(2020, 1), 1606798800);
+    }
+
+    #[test]
+    fn test_format_seconds() {
+        assert_eq!(format_seconds(6), "6s");
+        assert_eq!(format_seconds(245), "4m05s");
+        assert_eq!(format_seconds(3723), "1h02m03s");
+    }
+
+    #[test]
+    fn test_tables() {
+        let leaderboard = parse(DATA).unwrap();
+
+        let ranking = ranking(&leaderboard);
+        assert!(ranking.contains("   1  Dana                       4     19\n"));
+        assert!(ranking.contains("   3  (anonymous user #103)      1      4\n"));
+
+        let deltas = deltas(&leaderboard);
+        assert!(deltas.contains("Carson                     5m00s      8m20s\n"));
+        assert!(deltas.contains("(anonymous user #103)          -          -\n"));
+
+        let reports = vec![DayReport {
+            day: 1,
+            implementation: String::from("default"),
+            source: String::from("src/challenges/day1.rs"),
+            input: None,
+            parts: vec![PartReport { part: 1, outcome: Outcome::Solved(String::from("7")), elapsed: Duration::from_millis(3) }],
+            render: None,
+        }];
+        let comparison = comparison(&leaderboard, &reports);
+        assert!(comparison.contains("  1      3m20s      5m20s      2m00s           3ms             -\n"));
+        assert!(comparison.contains("  2      3m20s     10m00s      6m40s             -             -\n"));
+    }
+}
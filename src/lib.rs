@@ -0,0 +1,17 @@
+pub mod abi;
+pub mod answers;
+pub mod cancel;
+pub mod challenges;
+pub mod client;
+pub mod config;
+pub mod counter;
+pub mod examples;
+pub mod grid;
+pub mod ffi;
+pub mod geometry;
+pub mod input;
+pub mod leaderboard;
+pub mod plugin;
+pub mod report;
+pub mod runner;
+pub mod server;
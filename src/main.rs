@@ -1,48 +1,402 @@
-use std::fs::File;
-use std::io::{self, BufRead};
 use std::path::Path;
+use std::process;
 
 use clap::Parser;
 
 extern crate clap;
-mod challenges;
+
+use aoc21::challenges::{Challenge, Registry, Source};
+use aoc21::answers::{AnswerStore, Known};
+use aoc21::client::{self, Client, Fetched, Verdict};
+use aoc21::config::{self, Config};
+use aoc21::runner::{self, Outcome};
+use aoc21::{examples, ffi, input, leaderboard, report, server};
 
 #[derive(Parser)]
 #[clap(version = "1.0", author = "Carson Myers <carson@myers.se>")]
 struct Opts {
-    day: u8,
-    part: u8,
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    day: Option<u8>,
+    part: Option<u8>,
 
     #[clap(short, long)]
     file: Option<String>,
+
+    /// Name of the implementation to run, for days with more than one
+    #[clap(long = "impl")]
+    implementation: Option<String>,
+
+    /// Run every implementation of the day and check that they agree
+    #[clap(long, conflicts_with = "implementation")]
+    all_impls: bool,
+
+    /// Challenge parameter as `name=value`, e.g. `days=80` for day 6
+    #[clap(long, multiple_occurrences = true, number_of_values = 1)]
+    param: Vec<String>,
+
+    /// Read day 2's input as a course script and print the commands it
+    /// expands to, rather than following them
+    #[clap(long)]
+    expand: bool,
+
+    /// Give up on a challenge after this many seconds
+    #[clap(long, parse(try_from_str = config::parse_timeout))]
+    timeout: Option<f64>,
+
+    /// Directory to load plugin libraries from
+    #[clap(long)]
+    plugin_dir: Option<String>,
+
+    /// Directory to read puzzle inputs from
+    #[clap(long)]
+    input_dir: Option<String>,
+
+    /// Config file to read instead of aoc21.toml
+    #[clap(long)]
+    config: Option<String>,
 }
 
-fn main() {
-    let opts: Opts = Opts::parse();
+#[derive(Parser)]
+enum Command {
+    /// Inspect the project configuration
+    Config {
+        #[clap(subcommand)]
+        command: ConfigCommand,
+    },
 
-    let filename = vec![
-        opts.file,
-        Some(format!("input/day{}_part{}", opts.day, opts.part)),
-        Some(format!("input/day{}", opts.day)),
-    ].into_iter()
-        .filter_map(|filename| filename)
-        .find(|filename| Path::new(filename).exists())
-        .expect("no input file for challenge");
+    /// Save the example from a downloaded puzzle page as a test fixture
+    Examples {
+        /// Puzzle description HTML file
+        page: String,
+        day: u8,
+
+        /// Directory to write fixtures to
+        #[clap(long, default_value = "tests/fixtures")]
+        dir: String,
+    },
+
+    /// Download the puzzle input for a day, unless it's already saved
+    Fetch {
+        day: u8,
+
+        /// Puzzle year, if not the configured one
+        #[clap(long)]
+        year: Option<u16>,
+    },
+
+    /// Print the C header for the shared library
+    Header,
 
-    let input = File::open(filename)
-        .map(|file| io::BufReader::new(file)
-            .lines()
-            .filter_map(|line| line.ok())
-            .collect::<Vec<String>>())
+    /// Show statistics from an exported private leaderboard
+    Leaderboard {
+        /// Leaderboard JSON file
+        file: String,
+
+        /// Also run every day and compare against our own timings
+        #[clap(long)]
+        compare: bool,
+    },
+
+    /// List every available challenge implementation
+    List,
+
+    /// Run every day and write up the results
+    Report {
+        /// Document format: md or html
+        #[clap(long)]
+        format: Option<report::Format>,
+    },
+
+    /// Serve the solvers over HTTP on localhost
+    Serve {
+        #[clap(short, long, default_value = "8021")]
+        port: u16,
+    },
+
+    /// Solve a part and submit the answer
+    Submit {
+        day: u8,
+        part: u8,
+
+        /// Puzzle year, if not the configured one
+        #[clap(long)]
+        year: Option<u16>,
+    },
+}
+
+#[derive(Parser)]
+enum ConfigCommand {
+    /// Print the configuration after applying command line flags
+    Show,
+}
+
+/// Load the config file and apply any flags given on the command line over
+/// the top of it
+fn load_config(opts: &Opts) -> Config {
+    let mut config = Config::load(opts.config.as_deref().map(Path::new))
+        .unwrap_or_else(|err| panic!("{}", err));
+
+    if let Some(dir) = &opts.input_dir {
+        config.input_dir = dir.clone();
+    }
+    if let Some(dir) = &opts.plugin_dir {
+        config.plugin_dir = dir.clone();
+    }
+    if opts.timeout.is_some() {
+        config.timeout = opts.timeout;
+    }
+    if let Some(Command::Report { format: Some(format) }) = &opts.command {
+        config.format = *format;
+    }
+
+    // Parameters on the command line apply to the day being run
+    let day = match &opts.command {
+        Some(Command::Submit { day, .. }) => Some(*day),
+        _ => opts.day,
+    };
+    if let Some(day) = day {
+        for param in &opts.param {
+            let (name, value) = param.split_once('=')
+                .unwrap_or_else(|| panic!("parameters must be given as name=value: {}", param));
+            config.params.entry(day)
+                .or_default()
+                .insert(name.to_string(), value.to_string());
+        }
+    }
+    if opts.expand {
+        if day != Some(2) {
+            panic!("--expand only applies to day 2");
+        }
+
+        let params = config.params.entry(2).or_default();
+        params.insert(String::from("input"), String::from("script"));
+        params.insert(String::from("expand"), String::from("true"));
+    }
+
+    config
+}
+
+/// Build the registry of built-in challenges plus any plugins, warning about
+/// plugins that couldn't be loaded
+fn load_registry(plugin_dir: &str) -> Registry {
+    let mut registry = Registry::builtin();
+
+    let plugin_dir = Path::new(plugin_dir);
+    if plugin_dir.exists() {
+        registry.load_plugins(plugin_dir);
+    }
+
+    for err in &registry.errors {
+        eprintln!("warning: {}", err);
+    }
+
+    registry
+}
+
+fn list(registry: &Registry) {
+    for implementation in registry.implementations() {
+        match &implementation.source {
+            Source::Builtin(_) =>
+                println!("day{}::{}", implementation.day, implementation.name),
+            Source::Plugin(plugin) =>
+                println!("day{}::{} (plugin: {})", implementation.day, implementation.name,
+                    plugin.path.display()),
+        }
+    }
+
+    let embedded = input::embedded();
+    if !embedded.is_empty() {
+        println!("embedded inputs: {}", embedded.join(", "));
+    }
+}
+
+/// Apply the configured parameters for a day to a freshly constructed
+/// challenge
+fn configure(challenge: &mut dyn Challenge, config: &Config, day: u8) {
+    for (name, value) in config.params_for(day) {
+        challenge.set_param(&name, &value)
+            .unwrap_or_else(|err| panic!("{}", err));
+    }
+}
+
+/// Run every implementation of a day on the same input, reporting how long
+/// each took. Exits with an error if any of them fail or they don't all
+/// produce the same answer.
+fn run_all(registry: &Registry, config: &Config, day: u8, part: u8, input: Vec<String>) {
+    let implementations = registry.implementations_for(day);
+    if implementations.is_empty() {
+        panic!("no challenge for day {}", day);
+    }
+
+    let outcomes = implementations.iter()
+        .map(|implementation| {
+            let mut challenge = implementation.new_challenge();
+            configure(challenge.as_mut(), config, day);
+
+            let (outcome, elapsed) = runner::run(challenge, part, input.clone(), config.timeout());
+            println!("day{}::{}\t{}\t({:?})", day, implementation.name, outcome, elapsed);
+            outcome
+        })
+        .collect::<Vec<_>>();
+
+    if outcomes.iter().any(|outcome| !matches!(outcome, Outcome::Solved(_))) {
+        eprintln!("error: not every implementation of day {} part {} finished", day, part);
+        process::exit(1);
+    }
+
+    if outcomes.iter().any(|outcome| *outcome != outcomes[0]) {
+        eprintln!("error: implementations of day {} part {} disagree", day, part);
+        process::exit(1);
+    }
+}
+
+/// Run one part of a day with the selected implementation, exiting if it
+/// doesn't produce an answer
+fn solve(registry: &Registry, config: &Config, opts: &Opts, day: u8, part: u8) -> String {
+    let source = input::resolve(&config.input_dir, day, part, opts.file.as_deref())
+        .expect("no input file for challenge");
+    let input = source.read()
         .expect("cannot read input file");
 
+    let mut challenge = registry.get_challenge(day, opts.implementation.as_deref())
+        .unwrap_or_else(|err| panic!("{}", err));
+    configure(challenge.as_mut(), config, day);
+
+    match runner::run(challenge, part, input, config.timeout()) {
+        (Outcome::Solved(result), _) => result,
+        (Outcome::TimedOut, elapsed) => {
+            println!("timeout: no result after {:?}", elapsed);
+            process::exit(124);
+        },
+        (Outcome::Failed(message), _) => {
+            eprintln!("error: {}", message);
+            process::exit(1);
+        },
+    }
+}
 
-    let challenge = challenges::get_challenge(opts.day);
-    let result = match opts.part {
-        1 => challenge.part_1(input),
-        2 => challenge.part_2(input),
-        p @ _ => panic!("invalid part: {}", p),
+/// Submit the answer to a part, unless the answer store already knows what
+/// the site would say
+fn submit(config: &Config, year: u16, day: u8, part: u8, answer: &str) {
+    let fail = |err: &dyn std::fmt::Display| -> ! {
+        eprintln!("error: {}", err);
+        process::exit(1);
     };
 
-    println!("result: {}", result);
+    let mut store = AnswerStore::load(Path::new(&config.cache_dir))
+        .unwrap_or_else(|err| fail(&err));
+
+    if let Some(known) = store.check(year, day, part, answer) {
+        println!("{} (not submitted)", known);
+        if known != Known::Correct {
+            process::exit(1);
+        }
+        return;
+    }
+
+    let verdict = Client::new(config)
+        .and_then(|client| client.submit(year, day, part, answer))
+        .unwrap_or_else(|err| fail(&err));
+
+    store.record(year, day, part, answer, &verdict);
+    store.save().unwrap_or_else(|err| fail(&err));
+
+    println!("{}", verdict);
+    if !matches!(verdict, Verdict::Correct | Verdict::AlreadySolved) {
+        process::exit(1);
+    }
+}
+
+fn main() {
+    let opts: Opts = Opts::parse();
+    let config = load_config(&opts);
+    let registry = load_registry(&config.plugin_dir);
+
+    match opts.command {
+        Some(Command::Config { command: ConfigCommand::Show }) => {
+            // Never echo the session token
+            let mut config = config;
+            if config.session.is_some() {
+                config.session = Some(String::from("(hidden)"));
+            }
+            print!("{}", config.to_toml());
+            return;
+        },
+        Some(Command::Examples { page, day, dir }) => {
+            let html = std::fs::read_to_string(&page)
+                .unwrap_or_else(|err| panic!("cannot read {}: {}", page, err));
+            let fixture = examples::fixture(&examples::extract(&html))
+                .unwrap_or_else(|| panic!("no example found in {}", page));
+            for path in examples::write(Path::new(&dir), day, &fixture).expect("cannot write fixture") {
+                println!("wrote {}", path.display());
+            }
+            return;
+        },
+        Some(Command::Fetch { day, year }) => {
+            match client::fetch(&config, year.unwrap_or(config.year), day) {
+                Ok(Fetched::Downloaded(path)) => println!("saved {}", path.display()),
+                Ok(Fetched::Existing(path)) => println!("already downloaded: {}", path.display()),
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    process::exit(1);
+                },
+            }
+            return;
+        },
+        Some(Command::Header) => {
+            print!("{}", ffi::header());
+            return;
+        },
+        Some(Command::Leaderboard { file, compare }) => {
+            let text = std::fs::read_to_string(&file)
+                .unwrap_or_else(|err| panic!("cannot read {}: {}", file, err));
+            let board = leaderboard::parse(&text)
+                .unwrap_or_else(|err| panic!("{}", err));
+
+            print!("{}\n{}", leaderboard::ranking(&board), leaderboard::deltas(&board));
+            if compare {
+                let days = report::run(&registry, &config);
+                print!("\n{}", leaderboard::comparison(&board, &days));
+            }
+            return;
+        },
+        Some(Command::List) => {
+            list(&registry);
+            return;
+        },
+        Some(Command::Report { .. }) => {
+            let days = report::run(&registry, &config);
+            print!("{}", report::generate(&days, config.format));
+            return;
+        },
+        Some(Command::Serve { port }) => {
+            server::serve(port, registry, config).expect("cannot run server");
+            return;
+        },
+        Some(Command::Submit { day, part, year }) => {
+            let answer = solve(&registry, &config, &opts, day, part);
+            println!("answer: {}", answer);
+            submit(&config, year.unwrap_or(config.year), day, part, &answer);
+            return;
+        },
+        None => {},
+    }
+
+    let (day, part) = match (opts.day, opts.part) {
+        (Some(day), Some(part)) => (day, part),
+        _ => panic!("a day and part are required"),
+    };
+
+    if opts.all_impls {
+        let source = input::resolve(&config.input_dir, day, part, opts.file.as_deref())
+            .expect("no input file for challenge");
+        let input = source.read()
+            .expect("cannot read input file");
+        run_all(&registry, &config, day, part, input);
+        return;
+    }
+
+    println!("result: {}", solve(&registry, &config, &opts, day, part));
 }
@@ -0,0 +1,184 @@
+use std::env::consts::DLL_EXTENSION;
+use std::ffi::CStr;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use libloading::Library;
+
+use crate::abi::{self, ChallengesFn, SolveFn, VersionFn};
+use crate::challenges::Challenge;
+
+/// Anything that can go wrong while loading a plugin library
+#[derive(Debug)]
+pub enum PluginError {
+    /// The plugin directory couldn't be read
+    Dir(PathBuf, io::Error),
+
+    /// The library couldn't be loaded at all
+    Load(PathBuf, libloading::Error),
+
+    /// The library doesn't export one of the required symbols
+    MissingSymbol(PathBuf, &'static str),
+
+    /// The library was built against a different version of the ABI
+    AbiMismatch { path: PathBuf, expected: u32, found: u32 },
+
+    /// The library's challenge table is malformed
+    InvalidTable(PathBuf, String),
+}
+
+impl Display for PluginError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PluginError::Dir(path, err) =>
+                write!(f, "cannot read plugin directory {}: {}", path.display(), err),
+            PluginError::Load(path, err) =>
+                write!(f, "cannot load plugin {}: {}", path.display(), err),
+            PluginError::MissingSymbol(path, symbol) =>
+                write!(f, "plugin {} does not export `{}`", path.display(), symbol),
+            PluginError::AbiMismatch { path, expected, found } =>
+                write!(f, "plugin {} uses ABI version {}, but version {} is required",
+                    path.display(), found, expected),
+            PluginError::InvalidTable(path, reason) =>
+                write!(f, "plugin {} has an invalid challenge table: {}", path.display(), reason),
+        }
+    }
+}
+
+/// A challenge implementation exported by a plugin library. The library is
+/// kept loaded for as long as any of its challenges are alive.
+#[derive(Clone)]
+pub struct PluginChallenge {
+    pub day: u8,
+    pub name: String,
+    pub path: PathBuf,
+    solve: SolveFn,
+    _library: Arc<Library>,
+}
+
+impl PluginChallenge {
+    fn solve(&self, part: u8, input: Vec<String>) -> String {
+        let input = input.join("\n");
+
+        // Start with a buffer that fits any sensible answer, and grow it once
+        // if the plugin asks for more room
+        let mut capacity = 256;
+        for _ in 0..2 {
+            let mut buf = vec![0u8; capacity];
+            let mut len = capacity;
+            let status = unsafe {
+                (self.solve)(part, input.as_ptr(), input.len(), buf.as_mut_ptr(), &mut len)
+            };
+
+            match status {
+                abi::STATUS_OK => {
+                    buf.truncate(len.min(capacity));
+                    return String::from_utf8_lossy(&buf).into_owned();
+                },
+                abi::STATUS_BUFFER_TOO_SMALL if len > capacity => capacity = len,
+                status => panic!("plugin day{}::{} failed on part {}: {}",
+                    self.day, self.name, part, abi::describe_status(status)),
+            }
+        }
+
+        panic!("plugin day{}::{} kept asking for a larger output buffer", self.day, self.name);
+    }
+}
+
+impl Challenge for PluginChallenge {
+    fn part_1(&self, input: Vec<String>) -> String {
+        self.solve(1, input)
+    }
+
+    fn part_2(&self, input: Vec<String>) -> String {
+        self.solve(2, input)
+    }
+}
+
+/// Load every shared library in `dir`, returning all the challenges they
+/// export along with an error for each library that couldn't be used. A
+/// broken plugin doesn't prevent the others from loading.
+pub fn load_dir(dir: &Path) -> (Vec<PluginChallenge>, Vec<PluginError>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => return (Vec::new(), vec![PluginError::Dir(dir.to_path_buf(), err)]),
+    };
+
+    // Load libraries in a stable order so that listings don't shuffle around
+    let mut paths = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == DLL_EXTENSION))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut challenges = Vec::new();
+    let mut errors = Vec::new();
+    for path in paths {
+        match load(&path) {
+            Ok(loaded) => challenges.extend(loaded),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    (challenges, errors)
+}
+
+/// Load a single plugin library and read its challenge table
+pub fn load(path: &Path) -> Result<Vec<PluginChallenge>, PluginError> {
+    let library = unsafe { Library::new(path) }
+        .map_err(|err| PluginError::Load(path.to_path_buf(), err))?;
+    let library = Arc::new(library);
+
+    let symbol_name = |symbol: &'static [u8]| {
+        std::str::from_utf8(&symbol[..symbol.len() - 1]).unwrap()
+    };
+
+    // Check the ABI version before touching anything else in the library,
+    // since the layout of the challenge table may have changed
+    let version = unsafe { library.get::<VersionFn>(abi::VERSION_SYMBOL) }
+        .map_err(|_| PluginError::MissingSymbol(path.to_path_buf(), symbol_name(abi::VERSION_SYMBOL)))?;
+    let found = unsafe { version() };
+    if found != abi::PLUGIN_ABI_VERSION {
+        return Err(PluginError::AbiMismatch {
+            path: path.to_path_buf(),
+            expected: abi::PLUGIN_ABI_VERSION,
+            found,
+        });
+    }
+
+    let table = unsafe { library.get::<ChallengesFn>(abi::CHALLENGES_SYMBOL) }
+        .map_err(|_| PluginError::MissingSymbol(path.to_path_buf(), symbol_name(abi::CHALLENGES_SYMBOL)))?;
+
+    let mut count = 0;
+    let descriptors = unsafe { table(&mut count) };
+    if descriptors.is_null() {
+        return Err(PluginError::InvalidTable(path.to_path_buf(), String::from("table is null")));
+    }
+
+    let descriptors = unsafe { std::slice::from_raw_parts(descriptors, count) };
+    descriptors.iter()
+        .map(|descriptor| {
+            if descriptor.name.is_null() {
+                return Err(PluginError::InvalidTable(path.to_path_buf(),
+                    format!("day {} has no name", descriptor.day)));
+            }
+
+            let name = unsafe { CStr::from_ptr(descriptor.name) }
+                .to_str()
+                .map_err(|_| PluginError::InvalidTable(path.to_path_buf(),
+                    format!("day {} has a name that isn't utf-8", descriptor.day)))?;
+
+            Ok(PluginChallenge {
+                day: descriptor.day,
+                name: name.to_string(),
+                path: path.to_path_buf(),
+                solve: descriptor.solve,
+                _library: Arc::clone(&library),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,347 @@
+use std::fmt::Write;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::challenges::{Challenge, Registry, Render, Source, Visual};
+use crate::config::Config;
+use crate::input;
+use crate::runner::{self, Outcome};
+
+/// Largest grid drawn in an HTML report, in cells along either side. Bigger
+/// grids are shrunk to fit by merging blocks of cells.
+const MAX_GRID_CELLS: usize = 200;
+
+/// Widest text render included in a markdown report
+const MAX_TEXT_WIDTH: usize = 120;
+
+/// Document format of a report
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Markdown,
+    Html,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "md" | "markdown" => Ok(Format::Markdown),
+            "html" => Ok(Format::Html),
+            _ => Err(format!("unknown report format: {}", s)),
+        }
+    }
+}
+
+/// The result of running one part of a day
+pub struct PartReport {
+    pub part: u8,
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+}
+
+/// Size of a day's input
+pub struct InputStats {
+    pub lines: usize,
+    pub bytes: usize,
+}
+
+/// Everything a report says about a single day
+pub struct DayReport {
+    pub day: u8,
+    pub implementation: String,
+
+    /// Where the implementation lives: a source file or a plugin library
+    pub source: String,
+    pub input: Option<InputStats>,
+    pub parts: Vec<PartReport>,
+
+    /// The render of the input, or how rendering it ended if it didn't
+    /// finish. Missing when the challenge has nothing to render.
+    pub render: Option<Result<Render, Outcome>>,
+}
+
+/// Run both parts of the default implementation of every registered day,
+/// with the inputs, parameters and timeout from `config`
+pub fn run(registry: &Registry, config: &Config) -> Vec<DayReport> {
+    registry.days().into_iter()
+        .map(|day| {
+            let implementation = registry.implementations_for(day)[0];
+            let source = match &implementation.source {
+                Source::Builtin(_) => format!("src/challenges/day{}.rs", day),
+                Source::Plugin(plugin) => plugin.path.display().to_string(),
+            };
+
+            // A parameter the challenge doesn't accept marks the day as
+            // failed rather than aborting the whole report
+            let configure = |mut challenge: Box<dyn Challenge>| -> Result<Box<dyn Challenge>, String> {
+                for (name, value) in config.params_for(day) {
+                    challenge.set_param(&name, &value)?;
+                }
+                Ok(challenge)
+            };
+
+            let mut report = DayReport {
+                day,
+                implementation: implementation.name.clone(),
+                source,
+                input: None,
+                parts: Vec::new(),
+                render: None,
+            };
+
+            for part in 1..=2 {
+                let source = input::resolve(&config.input_dir, day, part, None);
+                let lines = match source.and_then(|source| source.read().ok()) {
+                    Some(lines) => lines,
+                    None => continue,
+                };
+
+                // Describe and render the input the first part runs on
+                if report.input.is_none() {
+                    report.input = Some(InputStats {
+                        lines: lines.len(),
+                        bytes: lines.iter().map(|line| line.len() + 1).sum(),
+                    });
+                    report.render = configure(implementation.new_challenge()).ok()
+                        .and_then(|challenge| runner::render(challenge, lines.clone(), config.timeout()).transpose());
+                }
+
+                let (outcome, elapsed) = match configure(implementation.new_challenge()) {
+                    Ok(challenge) => runner::run(challenge, part, lines, config.timeout()),
+                    Err(err) => (Outcome::Failed(err), Duration::ZERO),
+                };
+                report.parts.push(PartReport { part, outcome, elapsed });
+            }
+
+            report
+        })
+        .collect()
+}
+
+/// Render a report in the given format
+pub fn generate(days: &[DayReport], format: Format) -> String {
+    match format {
+        Format::Markdown => markdown(days),
+        Format::Html => html(days),
+    }
+}
+
+fn outcome_text(outcome: &Outcome) -> String {
+    match outcome {
+        Outcome::Solved(answer) => answer.clone(),
+        Outcome::TimedOut => String::from("timed out"),
+        Outcome::Failed(message) => format!("failed: {}", message),
+    }
+}
+
+fn input_text(input: &Option<InputStats>) -> String {
+    match input {
+        Some(stats) => format!("{} lines, {} bytes", stats.lines, stats.bytes),
+        None => String::from("no input"),
+    }
+}
+
+pub fn markdown(days: &[DayReport]) -> String {
+    let mut doc = String::from("# Advent of Code 2021\n");
+
+    for day in days {
+        writeln!(doc, "\n## Day {}\n", day.day).unwrap();
+        writeln!(doc, "- Implementation: [day{}::{}]({})", day.day, day.implementation, day.source).unwrap();
+        writeln!(doc, "- Input: {}", input_text(&day.input)).unwrap();
+
+        if !day.parts.is_empty() {
+            doc += "\n| Part | Answer | Time |\n|---|---|---|\n";
+            for part in &day.parts {
+                writeln!(doc, "| {} | {} | {:?} |", part.part, outcome_text(&part.outcome), part.elapsed).unwrap();
+            }
+        }
+
+        if let Some(Err(outcome)) = &day.render {
+            writeln!(doc, "\n_Render {}._", outcome_text(outcome)).unwrap();
+        }
+        if let Some(Ok(render)) = &day.render {
+            let width = render.text.lines().map(|line| line.len()).max().unwrap_or(0);
+            if width <= MAX_TEXT_WIDTH {
+                writeln!(doc, "\n```text\n{}\n```", render.text.trim_end()).unwrap();
+            } else {
+                writeln!(doc, "\n_Render omitted: {} columns is too wide for text._", width).unwrap();
+            }
+        }
+    }
+
+    doc
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn html(days: &[DayReport]) -> String {
+    let mut doc = String::from("\
+<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Advent of Code 2021</title>
+<style>
+body { font-family: sans-serif; max-width: 60em; margin: auto; }
+table { border-collapse: collapse; }
+td, th { border: 1px solid #ccc; padding: 0.2em 0.6em; }
+svg { display: block; margin: 1em 0; background: #fafafa; }
+</style>
+</head>
+<body>
+<h1>Advent of Code 2021</h1>
+");
+
+    for day in days {
+        writeln!(doc, "<h2>Day {}</h2>", day.day).unwrap();
+        writeln!(doc, "<p>Implementation: <a href=\"{}\">day{}::{}</a><br>Input: {}</p>",
+            escape(&day.source), day.day, escape(&day.implementation), input_text(&day.input)).unwrap();
+
+        if !day.parts.is_empty() {
+            doc += "<table>\n<tr><th>Part</th><th>Answer</th><th>Time</th></tr>\n";
+            for part in &day.parts {
+                writeln!(doc, "<tr><td>{}</td><td>{}</td><td>{:?}</td></tr>",
+                    part.part, escape(&outcome_text(&part.outcome)), part.elapsed).unwrap();
+            }
+            doc += "</table>\n";
+        }
+
+        if let Some(Err(outcome)) = &day.render {
+            writeln!(doc, "<p>Render {}</p>", escape(&outcome_text(outcome))).unwrap();
+        }
+        if let Some(Ok(render)) = &day.render {
+            doc += &match &render.visual {
+                Visual::Grid(rows) => grid_svg(rows),
+                Visual::Series(values) => series_svg(values),
+            };
+        }
+    }
+
+    doc += "</body>\n</html>\n";
+    doc
+}
+
+/// Draw a grid as an SVG image, one pixel-like square per cell. Cells hit
+/// once are pale and cells hit more often are dark.
+fn grid_svg(rows: &[Vec<u32>]) -> String {
+    let height = rows.len();
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+
+    // Merge blocks of cells so the image never has more than
+    // MAX_GRID_CELLS along a side, keeping the largest count in each block
+    let scale = width.max(height).div_ceil(MAX_GRID_CELLS).max(1);
+    let (cols, lines) = (width.div_ceil(scale), height.div_ceil(scale));
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" width=\"600\" shape-rendering=\"crispEdges\">\n",
+        cols.max(1), lines.max(1));
+
+    // Draw all the squares of each shade as a single path to keep the
+    // document small - a full grid is tens of thousands of squares
+    let (mut once, mut many) = (String::new(), String::new());
+    for y in 0..lines {
+        for x in 0..cols {
+            let count = rows[y * scale..((y + 1) * scale).min(height)].iter()
+                .flat_map(|row| row.iter().skip(x * scale).take(scale))
+                .max()
+                .copied()
+                .unwrap_or(0);
+
+            let path = match count {
+                0 => continue,
+                1 => &mut once,
+                _ => &mut many,
+            };
+            write!(path, "M{} {}h1v1h-1z", x, y).unwrap();
+        }
+    }
+
+    writeln!(svg, "<path fill=\"#9ecae1\" d=\"{}\"/>", once).unwrap();
+    writeln!(svg, "<path fill=\"#08306b\" d=\"{}\"/>", many).unwrap();
+    svg += "</svg>\n";
+    svg
+}
+
+/// Draw a series as an SVG bar chart, one bar per step
+fn series_svg(values: &[usize]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+    let (width, height) = (values.len().max(1) * 10, 200);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" width=\"600\">\n",
+        width, height + 20);
+
+    for (i, value) in values.iter().enumerate() {
+        let bar = (*value as f64 / max as f64 * height as f64).round() as usize;
+        writeln!(svg, "<rect x=\"{}\" y=\"{}\" width=\"8\" height=\"{}\" fill=\"#31a354\"><title>step {}: {}</title></rect>",
+            i * 10 + 1, height - bar, bar, i, value).unwrap();
+    }
+
+    writeln!(svg, "<text x=\"0\" y=\"{}\" font-size=\"12\">0</text>", height + 15).unwrap();
+    writeln!(svg, "<text x=\"{}\" y=\"{}\" font-size=\"12\" text-anchor=\"end\">{}</text>",
+        width, height + 15, values.len().saturating_sub(1)).unwrap();
+    svg += "</svg>\n";
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Vec<DayReport> {
+        vec![DayReport {
+            day: 5,
+            implementation: String::from("raster"),
+            source: String::from("src/challenges/day5.rs"),
+            input: Some(InputStats { lines: 2, bytes: 24 }),
+            parts: vec![
+                PartReport { part: 1, outcome: Outcome::Solved(String::from("5")), elapsed: Duration::from_millis(2) },
+                PartReport { part: 2, outcome: Outcome::TimedOut, elapsed: Duration::from_secs(1) },
+            ],
+            render: Some(Ok(Render {
+                text: String::from("1.\n.2\n"),
+                visual: Visual::Grid(vec![vec![1, 0], vec![0, 2]]),
+            })),
+        }]
+    }
+
+    #[test]
+    fn test_markdown() {
+        let doc = markdown(&example());
+        assert!(doc.contains("## Day 5\n"));
+        assert!(doc.contains("- Implementation: [day5::raster](src/challenges/day5.rs)\n"));
+        assert!(doc.contains("- Input: 2 lines, 24 bytes\n"));
+        assert!(doc.contains("| 1 | 5 | 2ms |\n| 2 | timed out | 1s |\n"));
+        assert!(doc.contains("```text\n1.\n.2\n```\n"));
+
+        let mut days = example();
+        days[0].render = Some(Err(Outcome::TimedOut));
+        assert!(markdown(&days).contains("\n_Render timed out._\n"));
+    }
+
+    #[test]
+    fn test_html() {
+        let doc = html(&example());
+        assert!(doc.contains("<a href=\"src/challenges/day5.rs\">day5::raster</a>"));
+        assert!(doc.contains("<tr><td>1</td><td>5</td><td>2ms</td></tr>"));
+        assert!(doc.contains("<path fill=\"#9ecae1\" d=\"M0 0h1v1h-1z\"/>"));
+        assert!(doc.contains("<path fill=\"#08306b\" d=\"M1 1h1v1h-1z\"/>"));
+    }
+
+    #[test]
+    fn test_grid_svg_downsamples() {
+        let rows = vec![vec![0; 1000]; 1000];
+        let mut rows = rows;
+        rows[999][999] = 3;
+
+        let svg = grid_svg(&rows);
+        assert!(svg.contains("viewBox=\"0 0 200 200\""));
+        assert!(svg.contains("d=\"M199 199h1v1h-1z\""));
+    }
+}
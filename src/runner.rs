@@ -0,0 +1,197 @@
+use std::any::Any;
+use std::fmt::{self, Display, Formatter};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cancel::{CancellationToken, Cancelled};
+use crate::challenges::{self, Challenge, InvalidInput, Render};
+
+/// How a single run of a challenge ended
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Solved(String),
+
+    /// The challenge didn't finish in time and was cancelled
+    TimedOut,
+
+    /// The challenge panicked, or there's no such part
+    Failed(String),
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Solved(answer) => write!(f, "result: {}", answer),
+            Outcome::TimedOut => write!(f, "timeout"),
+            Outcome::Failed(message) => write!(f, "failed: {}", message),
+        }
+    }
+}
+
+/// Run one part of a challenge on a worker thread, giving up once `timeout`
+/// has passed. A challenge that times out is cancelled so it stops at the
+/// next point it checks its token, but it isn't waited for. Returns the
+/// outcome along with how long the run took.
+pub fn run(
+    challenge: Box<dyn Challenge>,
+    part: u8,
+    input: Vec<String>,
+    timeout: Option<Duration>,
+) -> (Outcome, Duration) {
+    let (result, elapsed) = on_worker(challenge, timeout, move |challenge| {
+        challenges::run_part(challenge, part, input)
+    });
+
+    let outcome = match result {
+        Ok(Some(answer)) => Outcome::Solved(answer),
+        Ok(None) => Outcome::Failed(format!("invalid part: {}", part)),
+        Err(outcome) => outcome,
+    };
+    (outcome, elapsed)
+}
+
+/// Render a challenge's working state on a worker thread, with the same
+/// timeout and panic handling as `run`. A render that doesn't finish gives
+/// the outcome it ended with instead.
+pub fn render(
+    challenge: Box<dyn Challenge>,
+    input: Vec<String>,
+    timeout: Option<Duration>,
+) -> Result<Option<Render>, Outcome> {
+    on_worker(challenge, timeout, move |challenge| challenge.render(input)).0
+}
+
+/// Call `job` with the challenge on a worker thread, giving up once
+/// `timeout` has passed. Fails with the outcome that stopped it if it timed
+/// out or panicked.
+fn on_worker<T, F>(
+    mut challenge: Box<dyn Challenge>,
+    timeout: Option<Duration>,
+    job: F,
+) -> (Result<T, Outcome>, Duration)
+where
+    T: Send + 'static,
+    F: FnOnce(&dyn Challenge) -> T + Send + 'static,
+{
+    let token = CancellationToken::new();
+    challenge.set_cancellation(token.clone());
+
+    let (sender, receiver) = mpsc::channel();
+    let start = Instant::now();
+    let worker = thread::spawn(move || {
+        let result = job(challenge.as_ref());
+        let _ = sender.send((result, start.elapsed()));
+    });
+
+    let received = match timeout {
+        Some(timeout) => receiver.recv_timeout(timeout),
+        None => receiver.recv().map_err(RecvTimeoutError::from),
+    };
+
+    match received {
+        Ok((result, elapsed)) => (Ok(result), elapsed),
+
+        // The worker hung up without sending anything, so it panicked
+        Err(RecvTimeoutError::Disconnected) => {
+            let message = worker.join().err()
+                .map_or_else(|| String::from("challenge stopped"), panic_message);
+            (Err(Outcome::Failed(message)), start.elapsed())
+        },
+
+        Err(RecvTimeoutError::Timeout) => {
+            token.cancel();
+            (Err(Outcome::TimedOut), start.elapsed())
+        },
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if payload.is::<Cancelled>() {
+        String::from("cancelled")
+    } else if let Some(InvalidInput(message)) = payload.downcast_ref::<InvalidInput>() {
+        message.clone()
+    } else if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("challenge panicked")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A challenge that never finishes unless it's cancelled
+    struct Forever {
+        cancel: CancellationToken,
+        stopped: mpsc::Sender<()>,
+    }
+
+    impl Challenge for Forever {
+        fn part_1(&self, _input: Vec<String>) -> String {
+            struct Stopped<'a>(&'a mpsc::Sender<()>);
+            impl Drop for Stopped<'_> {
+                fn drop(&mut self) {
+                    let _ = self.0.send(());
+                }
+            }
+
+            let _stopped = Stopped(&self.stopped);
+            loop {
+                self.cancel.check();
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+
+        fn part_2(&self, _input: Vec<String>) -> String {
+            panic!("no part 2");
+        }
+
+        fn render(&self, input: Vec<String>) -> Option<Render> {
+            self.part_1(input);
+            None
+        }
+
+        fn set_cancellation(&mut self, token: CancellationToken) {
+            self.cancel = token;
+        }
+    }
+
+    fn forever() -> (Box<dyn Challenge>, mpsc::Receiver<()>) {
+        let (stopped, receiver) = mpsc::channel();
+        let challenge = Forever { cancel: CancellationToken::new(), stopped };
+        (Box::new(challenge), receiver)
+    }
+
+    #[test]
+    fn test_timeout() {
+        let (challenge, stopped) = forever();
+        let (outcome, _) = run(challenge, 1, Vec::new(), Some(Duration::from_millis(20)));
+        assert_eq!(outcome, Outcome::TimedOut);
+
+        // The worker unwinds once it notices it has been cancelled
+        assert!(stopped.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+
+    #[test]
+    fn test_failed() {
+        let (challenge, _) = forever();
+        let (outcome, _) = run(challenge, 2, Vec::new(), Some(Duration::from_secs(5)));
+        assert_eq!(outcome, Outcome::Failed(String::from("no part 2")));
+
+        let (challenge, _) = forever();
+        let (outcome, _) = run(challenge, 3, Vec::new(), None);
+        assert_eq!(outcome, Outcome::Failed(String::from("invalid part: 3")));
+    }
+
+    #[test]
+    fn test_render_timeout() {
+        let (challenge, stopped) = forever();
+        let rendered = render(challenge, Vec::new(), Some(Duration::from_millis(20)));
+        assert!(matches!(rendered, Err(Outcome::TimedOut)));
+        assert!(stopped.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+use serde_json::{json, Value};
+
+use crate::challenges::Registry;
+use crate::config::Config;
+use crate::runner::{self, Outcome};
+
+/// Largest request body the server is willing to read - puzzle inputs are
+/// only ever a few tens of kilobytes
+const MAX_BODY: usize = 16 * 1024 * 1024;
+
+/// A minimal HTTP request: just enough to route and solve a challenge
+struct Request {
+    method: String,
+    path: String,
+    body: String,
+}
+
+impl Request {
+    /// Read a request from a connection. Only `Content-Length` bodies are
+    /// supported, which is all any reasonable client will send for a
+    /// puzzle input.
+    fn read(stream: &mut impl BufRead) -> io::Result<Request> {
+        let mut request_line = String::new();
+        stream.read_line(&mut request_line)?;
+
+        // The request line looks like "POST /solve/1/2 HTTP/1.1"
+        let mut parts = request_line.split_whitespace();
+        let (method, path) = match (parts.next(), parts.next()) {
+            (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+            _ => return Err(invalid("malformed request line")),
+        };
+
+        // Read headers up to the blank line separating them from the body
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            if stream.read_line(&mut line)? == 0 {
+                break;
+            }
+
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+
+            if let Some((name, value)) = line.split_once(':') {
+                headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+            }
+        }
+
+        let length = match headers.get("content-length") {
+            Some(len) => len.parse::<usize>()
+                .map_err(|_| invalid("invalid content-length"))?,
+            None => 0,
+        };
+        if length > MAX_BODY {
+            return Err(invalid("request body too large"));
+        }
+
+        let mut body = vec![0; length];
+        stream.read_exact(&mut body)?;
+        let body = String::from_utf8(body)
+            .map_err(|_| invalid("request body is not utf-8"))?;
+
+        Ok(Request { method, path, body })
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Status code and JSON body to send back to the client
+struct Response {
+    status: u16,
+    body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Response {
+            status,
+            body: json!({ "error": message }),
+        }
+    }
+
+    fn write(&self, stream: &mut impl Write) -> io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            422 => "Unprocessable Entity",
+            504 => "Gateway Timeout",
+            _ => "Internal Server Error",
+        };
+
+        let body = self.body.to_string();
+        write!(stream, "HTTP/1.1 {} {}\r\n", self.status, reason)?;
+        write!(stream, "Content-Type: application/json\r\n")?;
+        write!(stream, "Content-Length: {}\r\n", body.len())?;
+        write!(stream, "Connection: close\r\n\r\n")?;
+        stream.write_all(body.as_bytes())?;
+        stream.flush()
+    }
+}
+
+/// Bind to `port` on localhost and serve solve requests from `registry`
+/// until the process is killed, with the parameters and timeout from
+/// `config`. Port 0 picks any free port; the bound address is printed
+/// either way so callers can find the server.
+pub fn serve(port: u16, registry: Registry, config: Config) -> io::Result<()> {
+    let registry = Arc::new(registry);
+    let config = Arc::new(config);
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    println!("listening on {}", listener.local_addr()?);
+    io::stdout().flush()?;
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let (registry, config) = (Arc::clone(&registry), Arc::clone(&config));
+                thread::spawn(move || {
+                    if let Err(err) = handle(stream, &registry, &config) {
+                        eprintln!("connection error: {}", err);
+                    }
+                });
+            },
+            Err(err) => eprintln!("accept error: {}", err),
+        }
+    }
+
+    Ok(())
+}
+
+fn handle(stream: TcpStream, registry: &Registry, config: &Config) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    let response = match Request::read(&mut reader) {
+        Ok(request) => route(&request, registry, config),
+        Err(err) => Response::error(400, &err.to_string()),
+    };
+
+    response.write(&mut writer)
+}
+
+fn route(request: &Request, registry: &Registry, config: &Config) -> Response {
+    let segments = request.path
+        .trim_matches('/')
+        .split('/')
+        .collect::<Vec<_>>();
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["days"]) => list_days(registry),
+        ("POST", ["solve", day, part]) => solve(registry, config, day, part, &request.body),
+        (_, ["days"]) | (_, ["solve", _, _]) => Response::error(405, "method not allowed"),
+        _ => Response::error(404, "not found"),
+    }
+}
+
+fn list_days(registry: &Registry) -> Response {
+    Response::ok(json!({ "days": registry.days() }))
+}
+
+fn solve(registry: &Registry, config: &Config, day: &str, part: &str, body: &str) -> Response {
+    let (day, part) = match (day.parse::<u8>(), part.parse::<u8>()) {
+        (Ok(day), Ok(part)) => (day, part),
+        _ => return Response::error(400, "day and part must be numbers"),
+    };
+    if !(1..=2).contains(&part) {
+        return Response::error(404, &format!("no part {} for day {}", part, day));
+    }
+
+    let mut challenge = match registry.get_challenge(day, None) {
+        Ok(challenge) => challenge,
+        Err(err) => return Response::error(404, &err.to_string()),
+    };
+    for (name, value) in config.params_for(day) {
+        if let Err(err) = challenge.set_param(&name, &value) {
+            return Response::error(500, &err);
+        }
+    }
+
+    let input = body.lines()
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    // Solve on the runner's worker thread, so a challenge that panics on
+    // malformed input or runs too long doesn't take the connection with it
+    match runner::run(challenge, part, input, config.timeout()) {
+        (Outcome::Solved(answer), elapsed) => Response::ok(json!({
+            "day": day,
+            "part": part,
+            "answer": answer,
+            "elapsed_us": elapsed.as_micros() as u64,
+        })),
+        (Outcome::TimedOut, elapsed) => Response::error(504, &format!("no answer after {:?}", elapsed)),
+        (Outcome::Failed(message), _) => Response::error(422, &format!("challenge failed: {}", message)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_request() {
+        let raw = "POST /solve/1/2 HTTP/1.1\r\nHost: localhost\r\nContent-Length: 7\r\n\r\n199\n200";
+        let request = Request::read(&mut raw.as_bytes()).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/solve/1/2");
+        assert_eq!(request.body, "199\n200");
+    }
+
+    #[test]
+    fn test_route() {
+        let request = |method: &str, path: &str| Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: String::new(),
+        };
+        let (registry, config) = (Registry::builtin(), Config::default());
+
+        assert_eq!(route(&request("GET", "/days"), &registry, &config).status, 200);
+        assert_eq!(route(&request("GET", "/solve/1/1"), &registry, &config).status, 405);
+        assert_eq!(route(&request("POST", "/solve/one/1"), &registry, &config).status, 400);
+        assert_eq!(route(&request("POST", "/solve/99/1"), &registry, &config).status, 404);
+        assert_eq!(route(&request("POST", "/solve/1/3"), &registry, &config).status, 404);
+        assert_eq!(route(&request("GET", "/nothing"), &registry, &config).status, 404);
+    }
+
+    #[test]
+    fn test_solve_with_config() {
+        let registry = Registry::builtin();
+        let mut config = Config::default();
+        config.params.entry(6).or_default().insert(String::from("days"), String::from("18"));
+        let response = solve(&registry, &config, "6", "1", "3,4,3,1,2");
+        assert_eq!(response.body["answer"], "26");
+
+        let response = solve(&registry, &config, "6", "1", "3,4,x");
+        assert_eq!(response.status, 422);
+        assert_eq!(response.body["error"], "challenge failed: invalid age: x (ages run from 0 to 8)");
+
+        // Far more input than can be solved in a millisecond
+        config.timeout = Some(0.001);
+        let input = (0..200_000).map(|depth| depth.to_string()).collect::<Vec<_>>().join("\n");
+        assert_eq!(solve(&registry, &config, "1", "1", &input).status, 504);
+    }
+}
@@ -0,0 +1,81 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Make an empty working directory containing the given config file
+fn workdir(name: &str, config: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("aoc21.toml"), config).unwrap();
+    dir
+}
+
+fn aoc21(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aoc21"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_show_merged() {
+    let dir = workdir("config_show", "input_dir = \"puzzles\"\ntimeout = 2.5\n\n[params.day6]\ndays = 18\n");
+
+    let output = aoc21(&dir, &["config", "show"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("input_dir = \"puzzles\"\n"));
+    assert!(stdout.contains("timeout = 2.5\n"));
+    assert!(stdout.contains("[params.day6]\ndays = \"18\"\n"));
+
+    // Flags on the command line win over the file
+    let output = aoc21(&dir, &["--input-dir", "elsewhere", "--timeout", "1", "config", "show"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("input_dir = \"elsewhere\"\n"));
+    assert!(stdout.contains("timeout = 1.0\n"));
+}
+
+#[test]
+fn test_show_hides_session() {
+    let dir = workdir("config_session", "session = \"secret\"\n");
+    let output = aoc21(&dir, &["config", "show"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("session = \"(hidden)\"\n"));
+    assert!(!stdout.contains("secret"));
+}
+
+#[test]
+fn test_params_and_input_dir() {
+    let dir = workdir("config_params", "input_dir = \"puzzles\"\n\n[params.day6]\ndays = 18\n");
+    fs::create_dir_all(dir.join("puzzles")).unwrap();
+    fs::write(dir.join("puzzles/day6"), "3,4,3,1,2\n").unwrap();
+
+    let output = aoc21(&dir, &["6", "1"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "result: 26\n");
+
+    let output = aoc21(&dir, &["--param", "days=80", "6", "1"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "result: 5934\n");
+}
+
+#[test]
+fn test_invalid_config() {
+    let dir = workdir("config_invalid", "colour = true\n");
+    let output = aoc21(&dir, &["config", "show"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap()
+        .contains("invalid config aoc21.toml: unexpected setting `colour` = true"));
+}
+
+#[test]
+fn test_expand() {
+    let dir = workdir("config_expand", "input_dir = \"puzzles\"\n");
+    fs::create_dir_all(dir.join("puzzles")).unwrap();
+    fs::write(dir.join("puzzles/day2"), "repeat 2 { down 3 }\nforward 4\n").unwrap();
+
+    let output = aoc21(&dir, &["--expand", "2", "1"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "result: down 3\ndown 3\nforward 4\n\n");
+
+    let output = aoc21(&dir, &["--expand", "6", "1"]);
+    assert!(String::from_utf8(output.stderr).unwrap().contains("--expand only applies to day 2"));
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "embed-inputs")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Make an empty working directory, with no input directory in it
+fn workdir(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn aoc21(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aoc21"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_falls_back_to_embedded() {
+    let dir = workdir("embed_fallback");
+
+    // The embedded input gives the same answer as the checked out one
+    let expected = aoc21(Path::new(env!("CARGO_MANIFEST_DIR")), &["1", "1"]);
+    let output = aoc21(&dir, &["1", "1"]);
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected.stdout);
+
+    // A file on disk still wins
+    fs::create_dir_all(dir.join("input")).unwrap();
+    fs::write(dir.join("input/day1"), "1\n2\n3\n").unwrap();
+    let output = aoc21(&dir, &["1", "1"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "result: 2\n");
+}
+
+#[test]
+fn test_list_shows_embedded() {
+    let output = aoc21(&workdir("embed_list"), &["list"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("embedded inputs: day1, day2, day3, day4, day5, day6\n"));
+}
@@ -0,0 +1,117 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+/// Stand in for the puzzle site: answer every request with `status` and
+/// `body`, and pass the request line and cookie of each one back
+fn mock_site(status: &'static str, body: &'static str) -> (String, Receiver<(String, String)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (send, recv) = mpsc::channel();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request = String::new();
+            reader.read_line(&mut request).unwrap();
+            let mut cookie = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("cookie") {
+                        cookie = value.trim().to_string();
+                    }
+                }
+            }
+
+            write!(stream, "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status, body.len(), body).unwrap();
+            send.send((request.trim().to_string(), cookie)).unwrap();
+        }
+    });
+
+    (url, recv)
+}
+
+/// Make an empty working directory with a config pointing at `url`
+fn workdir(name: &str, url: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("aoc21.toml"), format!("base_url = \"{}\"\nrequest_interval = 0\n", url)).unwrap();
+    dir
+}
+
+fn aoc21(dir: &Path, session: Option<&str>, args: &[&str]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_aoc21"));
+    command.current_dir(dir).args(args).env_remove("AOC_SESSION");
+    if let Some(session) = session {
+        command.env("AOC_SESSION", session);
+    }
+    command.output().unwrap()
+}
+
+#[test]
+fn test_fetch() {
+    let (url, requests) = mock_site("200 OK", "199\n200\n208\n");
+    let dir = workdir("fetch", &url);
+
+    let output = aoc21(&dir, Some("secret"), &["fetch", "1", "--year", "2020"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "saved input/day1\n");
+    assert_eq!(fs::read_to_string(dir.join("input/day1")).unwrap(), "199\n200\n208\n");
+    assert_eq!(requests.recv().unwrap(),
+        (String::from("GET /2020/day/1/input HTTP/1.1"), String::from("session=secret")));
+
+    // The saved input is where a run looks for it
+    let output = aoc21(&dir, None, &["1", "1"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "result: 2\n");
+
+    // A second fetch never reaches the site
+    let output = aoc21(&dir, Some("secret"), &["fetch", "1"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "already downloaded: input/day1\n");
+    assert!(requests.recv_timeout(Duration::from_millis(200)).is_err());
+}
+
+#[test]
+fn test_fetch_errors() {
+    let (url, requests) = mock_site("429 Too Many Requests", "slow down");
+    let dir = workdir("fetch_errors", &url);
+
+    let output = aoc21(&dir, None, &["fetch", "2"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("no session token"));
+
+    let output = aoc21(&dir, Some("secret"), &["fetch", "2"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("rate limited"));
+    assert_eq!(requests.recv().unwrap().0, "GET /2021/day/2/input HTTP/1.1");
+    assert!(!dir.join("input/day2").exists());
+}
+
+#[test]
+fn test_request_interval() {
+    let (url, requests) = mock_site("200 OK", "forward 5\n");
+    let dir = workdir("fetch_interval", &url);
+    fs::write(dir.join("aoc21.toml"), format!("base_url = \"{}\"\nrequest_interval = 1\n", url)).unwrap();
+
+    aoc21(&dir, Some("secret"), &["fetch", "2"]);
+    requests.recv().unwrap();
+
+    // The next request waits out the interval since the last one
+    let start = std::time::Instant::now();
+    aoc21(&dir, Some("secret"), &["fetch", "3"]);
+    requests.recv().unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(900));
+}
@@ -0,0 +1,54 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn test_header_is_current() {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc21"))
+        .arg("header")
+        .output()
+        .unwrap();
+    let committed = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("include/aoc21.h"))
+        .unwrap();
+
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), committed,
+        "include/aoc21.h is stale - regenerate it with `aoc21 header`");
+}
+
+#[test]
+fn test_c_program() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+    // Tests only build the rlib, so build the shared library into the same
+    // target directory as the binary before linking against it
+    let lib_dir = Path::new(env!("CARGO_BIN_EXE_aoc21")).parent().unwrap();
+    let mut build = Command::new(env::var("CARGO").unwrap_or_else(|_| String::from("cargo")));
+    build.args(["build", "--lib", "--manifest-path"])
+        .arg(manifest_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(lib_dir.parent().unwrap());
+    if lib_dir.ends_with("release") {
+        build.arg("--release");
+    }
+    assert!(build.status().expect("cannot run cargo").success(), "cannot build the shared library");
+
+    let program = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ffi_solve");
+
+    let status = Command::new("cc")
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg(manifest_dir.join("tests/ffi/solve.c"))
+        .arg("-o")
+        .arg(&program)
+        .arg("-L")
+        .arg(lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-laoc21")
+        .status()
+        .expect("cannot run cc");
+    assert!(status.success());
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
@@ -0,0 +1,99 @@
+/* Solves every built-in day's example through the shared library */
+#include <stdio.h>
+#include <string.h>
+
+#include "aoc21.h"
+
+struct example {
+    uint8_t day;
+    const char *input;
+    const char *answers[2];
+};
+
+static const struct example examples[] = {
+    { 1, "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n", { "7", "5" } },
+    { 2, "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n", { "150", "900" } },
+    { 3, "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n",
+      { "198", "230" } },
+    { 4, "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1\n"
+         "\n"
+         "22 13 17 11  0\n 8  2 23  4 24\n21  9 14 16  7\n 6 10  3 18  5\n 1 12 20 15 19\n"
+         "\n"
+         " 3 15  0  2 22\n 9 18 13 17  5\n19  8  7 25 23\n20 11 10 24  4\n14 21 16 12  6\n"
+         "\n"
+         "14 21 17 24  4\n10 16 15  9 19\n18  8 23 26 20\n22 11 13  6  5\n 2  0 12  3  7\n",
+      { "4512", "1924" } },
+    { 5, "0,9 -> 5,9\n8,0 -> 0,8\n9,4 -> 3,4\n2,2 -> 2,1\n7,0 -> 7,4\n"
+         "6,4 -> 2,0\n0,9 -> 2,9\n3,4 -> 1,4\n0,0 -> 8,8\n5,5 -> 8,2\n",
+      { "5", "12" } },
+    { 6, "3,4,3,1,2\n", { "5934", "26984457539" } },
+};
+
+static int expect_status(const char *what, int32_t status, int32_t expected) {
+    if (status != expected) {
+        fprintf(stderr, "%s: expected status %d, got %d\n", what, expected, status);
+        return 1;
+    }
+    return 0;
+}
+
+int main(void) {
+    int failures = 0;
+    uint8_t buf[64];
+    size_t len;
+
+    for (size_t i = 0; i < sizeof examples / sizeof examples[0]; i++) {
+        const struct example *ex = &examples[i];
+        for (uint8_t part = 1; part <= 2; part++) {
+            len = sizeof buf;
+            int32_t status = aoc21_solve(ex->day, part, (const uint8_t *)ex->input,
+                                         strlen(ex->input), buf, &len);
+            if (expect_status("solve", status, AOC21_STATUS_OK)) {
+                failures++;
+                continue;
+            }
+
+            const char *expected = ex->answers[part - 1];
+            if (len != strlen(expected) || memcmp(buf, expected, len) != 0) {
+                fprintf(stderr, "day %d part %d: expected %s, got %.*s\n",
+                        ex->day, part, expected, (int)len, buf);
+                failures++;
+            }
+        }
+    }
+
+    len = sizeof buf;
+    failures += expect_status("missing day", aoc21_solve(99, 1, NULL, 0, buf, &len),
+                              AOC21_STATUS_NO_SUCH_DAY);
+
+    len = sizeof buf;
+    failures += expect_status("missing part", aoc21_solve(1, 3, NULL, 0, buf, &len),
+                              AOC21_STATUS_NO_SUCH_PART);
+
+    len = sizeof buf;
+    failures += expect_status("null output", aoc21_solve(1, 1, NULL, 0, NULL, &len),
+                              AOC21_STATUS_NULL_POINTER);
+
+    len = sizeof buf;
+    failures += expect_status("bad input", aoc21_solve(1, 1, (const uint8_t *)"x\n", 2, buf, &len),
+                              AOC21_STATUS_INVALID_INPUT);
+
+    /* No board ever wins, which day 4 treats as a bug rather than bad input */
+    static const char no_winner[] =
+        "99\n\n1 2 3 4 5\n1 2 3 4 5\n1 2 3 4 5\n1 2 3 4 5\n1 2 3 4 5\n";
+    len = sizeof buf;
+    failures += expect_status("panic",
+                              aoc21_solve(4, 1, (const uint8_t *)no_winner, sizeof no_winner - 1, buf, &len),
+                              AOC21_STATUS_PANIC);
+
+    len = 4;
+    failures += expect_status("small buffer",
+                              aoc21_solve(6, 2, (const uint8_t *)"3,4,3,1,2", 9, buf, &len),
+                              AOC21_STATUS_BUFFER_TOO_SMALL);
+    if (len != 11) {
+        fprintf(stderr, "small buffer: expected required length 11, got %zu\n", len);
+        failures++;
+    }
+
+    return failures == 0 ? 0 : 1;
+}
@@ -0,0 +1,98 @@
+use std::env::consts::DLL_EXTENSION;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Compile the example plugin into a fresh plugin directory, built against
+/// the given ABI version
+fn plugin_dir(name: &str, abi_version: u32) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let status = Command::new("cc")
+        .args(["-shared", "-fPIC", "-I"])
+        .arg(manifest_dir.join("include"))
+        .arg("-o")
+        .arg(dir.join(format!("libcounter.{}", DLL_EXTENSION)))
+        .arg(format!("-DABI_VERSION={}", abi_version))
+        .arg(manifest_dir.join("tests/plugins/counter.c"))
+        .status()
+        .expect("cannot run cc");
+    assert!(status.success());
+
+    dir
+}
+
+fn aoc21(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aoc21"))
+        .arg("--plugin-dir")
+        .arg(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_list_plugins() {
+    let dir = plugin_dir("plugin_list", 1);
+    let output = aoc21(&dir, &["list"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.contains("day1::default\n"));
+    assert!(stdout.lines().any(|line| line.starts_with("day7::lines (plugin: ")));
+    assert!(stdout.lines().any(|line| line.starts_with("day1::lines (plugin: ")));
+}
+
+#[test]
+fn test_run_plugin() {
+    let dir = plugin_dir("plugin_run", 1);
+    let input = dir.join("input");
+    fs::write(&input, "a\nb\nc\n").unwrap();
+    let input = input.to_str().unwrap();
+
+    // Day 7 only exists in the plugin, so it's picked without `--impl`
+    let output = aoc21(&dir, &["7", "2", "-f", input]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "result: 6\n");
+
+    // Day 1 prefers the built-in unless the plugin is asked for by name
+    let output = aoc21(&dir, &["--impl", "day1::lines", "1", "1", "-f", input]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "result: 3\n");
+
+    let output = aoc21(&dir, &["--impl", "missing", "1", "1", "-f", input]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap()
+        .contains("no implementation `missing` for day 1 (available: default, lines)"));
+}
+
+#[test]
+fn test_abi_mismatch() {
+    let dir = plugin_dir("plugin_abi", 99);
+    let output = aoc21(&dir, &["list"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(output.status.success());
+    assert!(!stdout.contains("plugin"));
+    assert!(stderr.contains("uses ABI version 99, but version 1 is required"));
+}
+
+#[test]
+fn test_all_impls_disagree() {
+    let dir = plugin_dir("plugin_all_impls", 1);
+    let input = dir.join("input");
+    fs::write(&input, "199\n200\n208\n").unwrap();
+
+    // The plugin counts lines rather than depth increases, so it disagrees
+    // with the built-in implementation
+    let output = aoc21(&dir, &["--all-impls", "1", "1", "-f", input.to_str().unwrap()]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert!(stdout.contains("day1::default\tresult: 2\t"));
+    assert!(stdout.contains("day1::lines\tresult: 3\t"));
+    assert!(String::from_utf8(output.stderr).unwrap()
+        .contains("implementations of day 1 part 1 disagree"));
+}
@@ -0,0 +1,44 @@
+/* Example plugin: counts the lines of its input for day 7 and day 1 */
+#include <stdio.h>
+#include <string.h>
+
+#include "aoc21.h"
+
+static int32_t count_lines(uint8_t part, const uint8_t *input, size_t input_len,
+                           uint8_t *out_buf, size_t *out_len) {
+    if (part != 1 && part != 2) {
+        return AOC21_STATUS_NO_SUCH_PART;
+    }
+
+    size_t lines = input_len > 0 ? 1 : 0;
+    for (size_t i = 0; i < input_len; i++) {
+        if (input[i] == '\n') {
+            lines++;
+        }
+    }
+
+    char answer[32];
+    int len = snprintf(answer, sizeof answer, "%zu", lines * part);
+    if ((size_t)len > *out_len) {
+        *out_len = len;
+        return AOC21_STATUS_BUFFER_TOO_SMALL;
+    }
+
+    memcpy(out_buf, answer, len);
+    *out_len = len;
+    return AOC21_STATUS_OK;
+}
+
+static const struct aoc21_challenge challenges[] = {
+    { 7, "lines", count_lines },
+    { 1, "lines", count_lines },
+};
+
+uint32_t aoc21_plugin_abi_version(void) {
+    return ABI_VERSION;
+}
+
+const struct aoc21_challenge *aoc21_plugin_challenges(size_t *count) {
+    *count = sizeof challenges / sizeof challenges[0];
+    return challenges;
+}
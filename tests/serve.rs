@@ -0,0 +1,88 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+
+use serde_json::Value;
+
+/// A running `aoc21 serve` process, killed when dropped
+struct Server {
+    child: Child,
+    addr: SocketAddr,
+}
+
+impl Server {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_aoc21"))
+            .args(["serve", "--port", "0"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("cannot start server");
+
+        // The server announces the address it bound to on its first line
+        let mut stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+        let addr = line.trim()
+            .strip_prefix("listening on ")
+            .expect("server did not announce its address")
+            .parse()
+            .unwrap();
+
+        // Keep draining stdout so the server never blocks on a full pipe
+        thread::spawn(move || std::io::copy(&mut stdout, &mut std::io::sink()));
+
+        Server { child, addr }
+    }
+
+    fn request(&self, method: &str, path: &str, body: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(self.addr).unwrap();
+        write!(stream, "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+            method, path, body.len(), body).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn test_days() {
+    let server = Server::start();
+    let (status, body) = server.request("GET", "/days", "");
+    assert_eq!(status, 200);
+    assert_eq!(body["days"], serde_json::json!([1, 2, 3, 4, 5, 6]));
+}
+
+#[test]
+fn test_solve() {
+    let server = Server::start();
+    let input = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+
+    let (status, body) = server.request("POST", "/solve/1/1", input);
+    assert_eq!(status, 200);
+    assert_eq!(body["answer"], "7");
+    assert!(body["elapsed_us"].is_u64());
+
+    let (status, body) = server.request("POST", "/solve/1/2", input);
+    assert_eq!(status, 200);
+    assert_eq!(body["answer"], "5");
+}
+
+#[test]
+fn test_errors() {
+    let server = Server::start();
+    assert_eq!(server.request("POST", "/solve/99/1", "").0, 404);
+    assert_eq!(server.request("GET", "/solve/1/1", "").0, 405);
+    assert_eq!(server.request("POST", "/solve/1/1", "not a number").0, 422);
+}
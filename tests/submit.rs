@@ -0,0 +1,128 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+const INPUT: &str = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+
+fn page(message: &str) -> String {
+    format!("<!DOCTYPE html>\n<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>\n", message)
+}
+
+/// Stand in for the puzzle site, answering each submission with the page
+/// `respond` makes from the form it was sent. The forms are passed back as
+/// they arrive.
+fn fake_site(respond: fn(&str) -> String) -> (String, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (send, recv) = mpsc::channel();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+
+            let mut form = vec![0; length];
+            reader.read_exact(&mut form).unwrap();
+            let form = String::from_utf8(form).unwrap();
+
+            let body = respond(&form);
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(), body).unwrap();
+            send.send(form).unwrap();
+        }
+    });
+
+    (url, recv)
+}
+
+/// Make a working directory with the day 1 example as its input and a
+/// config pointing at `url`
+fn workdir(name: &str, url: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("input")).unwrap();
+    fs::write(dir.join("input/day1"), INPUT).unwrap();
+    fs::write(dir.join("aoc21.toml"), format!("base_url = \"{}\"\nrequest_interval = 0\n", url)).unwrap();
+    dir
+}
+
+fn aoc21(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aoc21"))
+        .current_dir(dir)
+        .args(args)
+        .env("AOC_SESSION", "secret")
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn test_submit() {
+    let (url, forms) = fake_site(|form| match form {
+        "level=1&answer=7" => page("That's the right answer!  You are <span>one gold star</span> closer."),
+        _ => page("That's not the right answer; your answer is too high.  If you're stuck..."),
+    });
+    let dir = workdir("submit", &url);
+
+    let output = aoc21(&dir, &["submit", "1", "1"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "answer: 7\ncorrect\n");
+    assert_eq!(forms.recv().unwrap(), "level=1&answer=7");
+
+    let output = aoc21(&dir, &["submit", "1", "2"]);
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "answer: 5\ntoo high\n");
+    assert_eq!(forms.recv().unwrap(), "level=2&answer=5");
+
+    // Both outcomes are remembered, so neither answer is sent again
+    let output = aoc21(&dir, &["submit", "1", "1"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "answer: 7\nalready submitted: correct (not submitted)\n");
+
+    let output = aoc21(&dir, &["submit", "1", "2"]);
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "answer: 5\nalready submitted: too high (not submitted)\n");
+
+    assert!(forms.recv_timeout(Duration::from_millis(200)).is_err());
+    let answers = fs::read_to_string(dir.join(".aoc21/answers.json")).unwrap();
+    assert!(answers.contains("\"2021/1/1\""));
+    assert!(answers.contains("\"verdict\": \"too high\""));
+}
+
+#[test]
+fn test_rate_limited() {
+    let (url, forms) = fake_site(|_| page(
+        "You gave an answer too recently; you have to wait after submitting an answer before trying again.  \
+         You have 58s left to wait. <a href=\"/2021/day/1\">[Return to Day 1]</a>"));
+    let dir = workdir("submit_rate_limited", &url);
+
+    let output = aoc21(&dir, &["submit", "1", "1"]);
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "answer: 7\nrate limited (58s left to wait)\n");
+
+    // The answer wasn't judged, so trying again goes to the site
+    aoc21(&dir, &["submit", "1", "1"]);
+    assert_eq!(forms.iter().take(2).count(), 2);
+}